@@ -0,0 +1,83 @@
+use crate::{Event, FrameController, Layer, Result};
+use std::time::Instant;
+
+// Returned by App::update and App::handle_event to keep going or to end
+// Runtime::run.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Control {
+    Continue,
+    Quit,
+}
+
+// An application driven by Runtime. Each frame update() runs, then view()
+// builds the content layers, then input events are handed to
+// handle_event() until the frame time is over.
+pub trait App {
+    // `tick` is FrameController::tick()
+    fn update(&mut self, _tick: u64) -> Control {
+        Control::Continue
+    }
+    fn view(&self, terminal_size: &(u16, u16), tick: u64) -> Vec<Layer>;
+    // raw mode swallows the interrupt signal, so Ctrl+C quits by default
+    fn handle_event(&mut self, event: Event) -> Control {
+        match event {
+            Event::Key(key) if key.is_ctrl('c') => Control::Quit,
+            _ => Control::Continue,
+        }
+    }
+}
+
+// Owns the FrameController and runs an App at one frame per `delay`. The
+// FrameController methods stay usable for anything the loop does not do.
+pub struct Runtime<A: App> {
+    pub app: A,
+    pub frame_controller: FrameController,
+}
+
+impl<A: App> Runtime<A> {
+    pub fn new(app: A) -> Self {
+        Self::with_controller(app, FrameController::new())
+    }
+    pub fn with_controller(app: A, frame_controller: FrameController) -> Self {
+        Self { app, frame_controller }
+    }
+    // Enters a TerminalSession and runs frames until the app returns
+    // Control::Quit. The terminal is restored on return, errors included.
+    pub fn run(&mut self) -> Result<()> {
+        let _session = self.frame_controller.enter_session()?;
+        self.frame_controller.set_terminal_to_current_size()?;
+        loop {
+            if self.frame() == Control::Quit {
+                return Ok(());
+            }
+            self.frame_controller.merge_layers()?;
+            self.frame_controller.draw()?;
+            let deadline = Instant::now() + self.frame_controller.frame_budget();
+            loop {
+                let timeout = deadline.saturating_duration_since(Instant::now());
+                match self.frame_controller.poll_event(timeout)? {
+                    Some(event) => {
+                        if self.app.handle_event(event) == Control::Quit {
+                            return Ok(());
+                        }
+                    }
+                    // a dropped key release also gives None, only the
+                    // deadline ends the frame
+                    None if Instant::now() < deadline => continue,
+                    None => break,
+                }
+            }
+            self.frame_controller.finish_frame();
+        }
+    }
+    // runs update() and hands the view to the FrameController
+    fn frame(&mut self) -> Control {
+        let tick = self.frame_controller.tick();
+        if self.app.update(tick) == Control::Quit {
+            return Control::Quit;
+        }
+        let layers = self.app.view(self.frame_controller.terminal_size.get_value(), tick);
+        self.frame_controller.set_values(None, Some(layers), None);
+        Control::Continue
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{Backend, Cell, ColorSupport, CountingWriter};
+use std::io::{self, Write};
+
+// Writes plain ANSI escape sequences to anything that is Write: a file, a
+// pipe, a pty or a socket. There is no terminal to ask, so the size is the
+// one it was given and the colors are sent as they are unless
+// set_color_support says otherwise.
+pub struct AnsiBackend<W: Write> {
+    out: CountingWriter<W>,
+    //     width height
+    //       v    v
+    size: (u16, u16),
+    color_support: ColorSupport,
+}
+
+impl<W: Write> AnsiBackend<W> {
+    pub fn new(out: W, size: (u16, u16)) -> Self {
+        Self {
+            out: CountingWriter::new(out),
+            size,
+            color_support: ColorSupport::TrueColor,
+        }
+    }
+    pub fn set_size(&mut self, size: (u16, u16)) {
+        self.size = size;
+    }
+}
+
+impl<W: Write> Backend for AnsiBackend<W> {
+    fn size(&self) -> io::Result<(u16, u16)> {
+        Ok(self.size)
+    }
+    fn clear(&mut self) -> io::Result<()> {
+        self.out.write_all(b"\x1B[2J\x1B[3J")
+    }
+    fn move_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
+        // ANSI counts from 1
+        write!(self.out, "\x1B[{};{}H", y as u32 + 1, x as u32 + 1)
+    }
+    fn write_cells(&mut self, cells: &[Cell]) -> io::Result<()> {
+        Cell::write_cells(cells, self.color_support, &mut self.out)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.out.write_all(b"\x1B[?25h")
+    }
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.out.write_all(b"\x1B[?25l")
+    }
+    fn set_color_support(&mut self, support: ColorSupport) {
+        self.color_support = support;
+    }
+    fn bytes_written(&self) -> usize {
+        self.out.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FrameController, Layer, LayerFillMode, Line, ManagmentMode};
+
+    #[test]
+    fn frames_are_written_as_plain_ansi() {
+        let mut controller = FrameController::new();
+        controller.size_managment_mode(ManagmentMode::Manual(1, 500));
+        controller.set_terminal_size((2, 2));
+        let mut layer = Layer::new(vec![Line::new("ab".to_string()), Line::new("cd".to_string())]);
+        layer.fill(&(2, 2), &LayerFillMode::Up(0));
+        controller.set_values(None, Some(vec![layer]), None);
+        controller.merge_layers().unwrap();
+        let mut out = vec![];
+        let mut backend = AnsiBackend::new(&mut out, (2, 2));
+        backend.set_color_support(ColorSupport::Mono);
+        controller.draw_to(&mut backend).unwrap();
+        let written = backend.bytes_written();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text, "\x1B[2J\x1B[3J\x1B[1;1H\x1B[0mab\x1B[0m\x1B[2;1H\x1B[0mcd\x1B[0m");
+        assert_eq!(controller.stats().bytes_written, written);
+        assert_eq!(written, text.len());
+    }
+}
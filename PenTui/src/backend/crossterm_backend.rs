@@ -0,0 +1,49 @@
+use crate::{Backend, Cell, ColorSupport, CountingWriter};
+use crossterm::{cursor, queue, terminal};
+use std::io::{self, Write};
+
+// Writes through crossterm commands to a terminal, usually stdout. The
+// size and the color support are the real terminal's.
+pub struct CrosstermBackend<W: Write> {
+    out: CountingWriter<W>,
+    color_support: ColorSupport,
+}
+
+impl<W: Write> CrosstermBackend<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out: CountingWriter::new(out),
+            color_support: ColorSupport::detect(),
+        }
+    }
+}
+
+impl<W: Write> Backend for CrosstermBackend<W> {
+    fn size(&self) -> io::Result<(u16, u16)> {
+        terminal::size()
+    }
+    fn clear(&mut self) -> io::Result<()> {
+        queue!(self.out, terminal::Clear(terminal::ClearType::All), terminal::Clear(terminal::ClearType::Purge))
+    }
+    fn move_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
+        queue!(self.out, cursor::MoveTo(x, y))
+    }
+    fn write_cells(&mut self, cells: &[Cell]) -> io::Result<()> {
+        Cell::write_cells(cells, self.color_support, &mut self.out)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+    fn show_cursor(&mut self) -> io::Result<()> {
+        queue!(self.out, cursor::Show)
+    }
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        queue!(self.out, cursor::Hide)
+    }
+    fn set_color_support(&mut self, support: ColorSupport) {
+        self.color_support = support;
+    }
+    fn bytes_written(&self) -> usize {
+        self.out.count
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{Cell, ColorSupport};
+use std::io::{self, Write};
+
+pub mod ansi_backend;
+pub mod crossterm_backend;
+pub mod test_backend;
+pub use ansi_backend::*;
+pub use crossterm_backend::*;
+pub use test_backend::*;
+
+// Where FrameController::draw_to sends its frames. Coordinates are
+// columns and rows from the top left corner of the screen.
+pub trait Backend {
+    //                  width height
+    //                    v    v
+    fn size(&self) -> io::Result<(u16, u16)>;
+    fn clear(&mut self) -> io::Result<()>;
+    fn move_cursor(&mut self, x: u16, y: u16) -> io::Result<()>;
+    // Writes the cells from the cursor on, continuation cells included,
+    // and leaves the cursor after the last one.
+    fn write_cells(&mut self, cells: &[Cell]) -> io::Result<()>;
+    fn flush(&mut self) -> io::Result<()>;
+    fn show_cursor(&mut self) -> io::Result<()>;
+    fn hide_cursor(&mut self) -> io::Result<()>;
+    // overrides what the backend would otherwise use, see ColorSupport
+    fn set_color_support(&mut self, _support: ColorSupport) {}
+    // everything written so far, for FrameStats::bytes_written
+    fn bytes_written(&self) -> usize {
+        0
+    }
+}
+
+// counts what a backend sends to its writer
+pub(crate) struct CountingWriter<W: Write> {
+    inner: W,
+    pub(crate) count: usize,
+}
+
+impl<W: Write> CountingWriter<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        Self { inner, count: 0 }
+    }
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written;
+        Ok(written)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{Backend, Cell, Layer};
+use std::io;
+
+// Keeps the grid that a terminal of `size` would show, for asserting on
+// frames without one. Nothing is written anywhere.
+#[derive(Clone, Debug)]
+pub struct TestBackend {
+    //        width height
+    //          v    v
+    pub size: (u16, u16),
+    pub buffer: Vec<Vec<Cell>>,
+    pub cursor: (u16, u16),
+    pub cursor_visible: bool,
+    pub flushes: usize,
+}
+
+impl TestBackend {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            size: (width, height),
+            buffer: vec![vec![Cell::blank(); width as usize]; height as usize],
+            cursor: (0, 0),
+            cursor_visible: true,
+            flushes: 0,
+        }
+    }
+    // the next FrameController::draw_to in Auto mode picks the size up
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.size = (width, height);
+        self.buffer.resize(height as usize, vec![]);
+        for row in self.buffer.iter_mut() {
+            row.resize(width as usize, Cell::blank());
+        }
+    }
+    pub fn cell(&self, x: u16, y: u16) -> Option<&Cell> {
+        self.buffer.get(y as usize)?.get(x as usize)
+    }
+    // the symbols of every row, one string per row
+    pub fn text(&self) -> Vec<String> {
+        self.buffer.iter().map(|row| row_text(row)).collect()
+    }
+    // Panics with both versions when the symbols on screen differ from
+    // `expected`, one string per row.
+    pub fn assert_text(&self, expected: &[&str]) {
+        assert_rows(&self.text(), expected);
+    }
+    // panics when the cell at (x, y) differs in symbol, colors or styles
+    pub fn assert_cell(&self, x: u16, y: u16, expected: &Cell) {
+        let actual = self.cell(x, y);
+        assert!(
+            actual == Some(expected),
+            "cell ({}, {}) differs\nexpected: {:?}\n  actual: {:?}",
+            x,
+            y,
+            expected,
+            actual
+        );
+    }
+}
+
+impl Backend for TestBackend {
+    fn size(&self) -> io::Result<(u16, u16)> {
+        Ok(self.size)
+    }
+    fn clear(&mut self) -> io::Result<()> {
+        for row in self.buffer.iter_mut() {
+            row.fill(Cell::blank());
+        }
+        Ok(())
+    }
+    fn move_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
+        self.cursor = (x, y);
+        Ok(())
+    }
+    fn write_cells(&mut self, cells: &[Cell]) -> io::Result<()> {
+        let (x, y) = (self.cursor.0 as usize, self.cursor.1 as usize);
+        if let Some(row) = self.buffer.get_mut(y) {
+            for (target, cell) in row.iter_mut().skip(x).zip(cells.iter()) {
+                *target = cell.clone();
+            }
+        }
+        self.cursor.0 = self.cursor.0.saturating_add(cells.len() as u16);
+        Ok(())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushes += 1;
+        Ok(())
+    }
+    fn show_cursor(&mut self) -> io::Result<()> {
+        self.cursor_visible = true;
+        Ok(())
+    }
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        self.cursor_visible = false;
+        Ok(())
+    }
+}
+
+// the symbols of every row of a layer, for the same kind of snapshot
+// without going through a FrameController
+pub fn layer_text(layer: &Layer) -> Vec<String> {
+    layer
+        .layer_lines
+        .get_value()
+        .iter()
+        .map(|line| row_text(line.line_content.get_value()))
+        .collect()
+}
+
+pub fn assert_layer_text(layer: &Layer, expected: &[&str]) {
+    assert_rows(&layer_text(layer), expected);
+}
+
+fn row_text(cells: &[Cell]) -> String {
+    cells.iter().map(|cell| cell.symbol.as_str()).collect()
+}
+
+fn assert_rows(actual: &[String], expected: &[&str]) {
+    if actual.iter().map(|row| row.as_str()).eq(expected.iter().copied()) {
+        return;
+    }
+    let frame = |rows: Vec<&str>| rows.iter().map(|row| format!("|{}|", row)).collect::<Vec<_>>().join("\n");
+    panic!(
+        "frame differs from the snapshot\nexpected:\n{}\n  actual:\n{}",
+        frame(expected.to_vec()),
+        frame(actual.iter().map(|row| row.as_str()).collect())
+    );
+}
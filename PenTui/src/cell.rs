@@ -0,0 +1,308 @@
+use crate::{indexed_to_rgb, Color, ColorSupport, Style, StylePatch, ANSI_16};
+use crossterm::{
+    queue,
+    style::{self, Attribute, SetAttribute, SetBackgroundColor, SetForegroundColor, SetUnderlineColor},
+};
+use std::io::{self, Write};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+pub const TAB_WIDTH: usize = 4;
+
+// One visible terminal column. Escape codes are only produced from
+// these values at draw time, never stored.
+// A grapheme wider than one column is followed by continuation cells with
+// an empty symbol, so a Vec<Cell> is always as long as it is wide.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Cell {
+    pub symbol: String,
+    pub fg: Color,
+    pub bg: Color,
+    pub modifiers: Style,
+    // for the underline modifiers, None keeps the text color
+    pub underline_color: Color,
+    // lets the layers below show through under Transparency::ExplicitTransparent
+    pub transparent: bool,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::blank()
+    }
+}
+
+impl Cell {
+    pub fn new(symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            fg: Color::None,
+            bg: Color::None,
+            modifiers: Style::NORMAL,
+            underline_color: Color::None,
+            transparent: false,
+        }
+    }
+    pub fn blank() -> Self {
+        Self::new(" ")
+    }
+    pub fn transparent() -> Self {
+        Self {
+            transparent: true,
+            ..Self::blank()
+        }
+    }
+    pub fn is_blank(&self) -> bool {
+        self.symbol == " "
+    }
+    pub fn is_continuation(&self) -> bool {
+        self.symbol.is_empty()
+    }
+    // display columns taken by the symbol, 0 for a continuation cell
+    pub fn width(&self) -> usize {
+        self.symbol.width()
+    }
+    // Splits text into grapheme clusters, one cell per column. Zero width
+    // clusters are joined to the cell before them. A tab becomes TAB_WIDTH
+    // blanks and other control characters become U+FFFD, so nothing can
+    // move the real cursor or start an escape sequence.
+    pub fn from_text(text: &str) -> Vec<Cell> {
+        let mut cells: Vec<Cell> = vec![];
+        for grapheme in text.graphemes(true) {
+            if grapheme == "\t" {
+                cells.extend(vec![Cell::blank(); TAB_WIDTH]);
+                continue;
+            }
+            let grapheme = if grapheme.chars().any(char::is_control) { "\u{FFFD}" } else { grapheme };
+            match grapheme.width() {
+                0 => {
+                    if let Some(last) = cells.iter_mut().rev().find(|c| !c.is_continuation()) {
+                        last.symbol.push_str(grapheme);
+                    }
+                }
+                width => {
+                    cells.push(Cell::new(grapheme));
+                    cells.extend(vec![Cell::new(""); width - 1]);
+                }
+            }
+        }
+        cells
+    }
+    // Blanks wide graphemes that lost part of their columns and
+    // continuation cells that lost their grapheme, after cutting or
+    // overlaying cells.
+    pub fn repair_wide(cells: &mut [Cell]) {
+        let mut covered_until = 0;
+        for i in 0..cells.len() {
+            if cells[i].is_continuation() {
+                if i >= covered_until {
+                    cells[i].symbol = " ".to_string();
+                }
+                continue;
+            }
+            let width = cells[i].width();
+            let whole = width <= 1
+                || (i + width <= cells.len() && cells[i + 1..i + width].iter().all(|c| c.is_continuation()));
+            if whole {
+                covered_until = i + width.max(1);
+            } else {
+                cells[i].symbol = " ".to_string();
+                covered_until = i + 1;
+            }
+        }
+    }
+    pub fn same_style(&self, other: &Cell) -> bool {
+        self.fg == other.fg && self.bg == other.bg && self.modifiers == other.modifiers && self.underline_color == other.underline_color
+    }
+    pub fn set_style(&mut self, patch: impl Into<StylePatch>) {
+        self.modifiers = patch.into().apply(self.modifiers);
+    }
+    pub fn write_style<W: Write>(&self, support: ColorSupport, out: &mut W) -> io::Result<()> {
+        queue!(out, SetAttribute(Attribute::Reset))?;
+        write_color(out, &self.fg, support, Ground::Fore)?;
+        write_color(out, &self.bg, support, Ground::Back)?;
+        write_color(out, &self.underline_color, support, Ground::Under)?;
+        for modifier in self.modifiers.iter() {
+            let attribute = match modifier {
+                Style::BOLD => Attribute::Bold,
+                Style::DIM => Attribute::Dim,
+                Style::ITALIC => Attribute::Italic,
+                Style::UNDERLINED => Attribute::Underlined,
+                Style::DOUBLE_UNDERLINED => Attribute::DoubleUnderlined,
+                Style::CURLY_UNDERLINED => Attribute::Undercurled,
+                Style::BLINK => Attribute::SlowBlink,
+                Style::REVERSE => Attribute::Reverse,
+                Style::HIDDEN => Attribute::Hidden,
+                Style::STRIKE => Attribute::CrossedOut,
+                Style::OVERLINE => Attribute::OverLined,
+                _ => continue,
+            };
+            queue!(out, SetAttribute(attribute))?;
+        }
+        Ok(())
+    }
+    pub fn write_cells<W: Write>(cells: &[Cell], support: ColorSupport, out: &mut W) -> io::Result<()> {
+        let mut current: Option<&Cell> = None;
+        for cell in cells.iter() {
+            if current.is_none_or(|c| !c.same_style(cell)) {
+                cell.write_style(support, out)?;
+                current = Some(cell);
+            }
+            // the terminal already moved past it when drawing the wide grapheme
+            if cell.is_continuation() {
+                continue;
+            }
+            out.write_all(cell.symbol.as_bytes())?;
+        }
+        queue!(out, SetAttribute(Attribute::Reset))
+    }
+}
+
+// what a color is written for
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Ground {
+    Fore,
+    Back,
+    Under,
+}
+
+fn write_color<W: Write>(out: &mut W, color: &Color, support: ColorSupport, ground: Ground) -> io::Result<()> {
+    let color = match term_color(color, support) {
+        Some(color) => color,
+        None => return Ok(()),
+    };
+    // crossterm sends the basic colors as 256 palette indexes, which
+    // 16 color terminals may not understand
+    if support == ColorSupport::Ansi16 && ground != Ground::Under {
+        if let Some(index) = BASIC.iter().position(|basic| *basic == color) {
+            let base = match (ground == Ground::Fore, index < 8) {
+                (true, true) => 30,
+                (true, false) => 90 - 8,
+                (false, true) => 40,
+                (false, false) => 100 - 8,
+            };
+            return write!(out, "\x1B[{}m", base + index);
+        }
+    }
+    match ground {
+        Ground::Fore => queue!(out, SetForegroundColor(color)),
+        Ground::Back => queue!(out, SetBackgroundColor(color)),
+        Ground::Under => queue!(out, SetUnderlineColor(color)),
+    }
+}
+
+// None when nothing should be written for the color
+fn term_color(color: &Color, support: ColorSupport) -> Option<style::Color> {
+    let color = match color {
+        Color::None => return None,
+        Color::Red => style::Color::DarkRed,
+        Color::Green => style::Color::DarkGreen,
+        Color::Blue => style::Color::DarkBlue,
+        Color::Dark => style::Color::Black,
+        Color::Yellow => style::Color::DarkYellow,
+        Color::Orange => style::Color::Rgb { r: 255, g: 165, b: 0 },
+        Color::Purple => style::Color::DarkMagenta,
+        Color::Grey => style::Color::Rgb { r: 128, g: 128, b: 128 },
+        Color::Cyan => style::Color::DarkCyan,
+        Color::White => style::Color::Grey,
+        Color::BrightDark => style::Color::DarkGrey,
+        Color::BrightRed => style::Color::Red,
+        Color::BrightGreen => style::Color::Green,
+        Color::BrightYellow => style::Color::Yellow,
+        Color::BrightBlue => style::Color::Blue,
+        Color::BrightPurple => style::Color::Magenta,
+        Color::BrightCyan => style::Color::Cyan,
+        Color::BrightWhite => style::Color::White,
+        Color::Reset => style::Color::Reset,
+        Color::Indexed(index) => style::Color::AnsiValue(*index),
+        Color::Rgb(r, g, b) => style::Color::Rgb { r: *r, g: *g, b: *b },
+    };
+    match (support, color) {
+        (ColorSupport::Mono, _) => None,
+        (ColorSupport::Ansi256, style::Color::Rgb { r, g, b }) => Some(style::Color::AnsiValue(rgb_to_256(r, g, b))),
+        (ColorSupport::Ansi16, style::Color::Rgb { r, g, b }) => Some(rgb_to_16(r, g, b)),
+        (ColorSupport::Ansi16, style::Color::AnsiValue(index)) => {
+            let (r, g, b) = indexed_to_rgb(index);
+            Some(rgb_to_16(r, g, b))
+        }
+        (_, color) => Some(color),
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+// the closest of the 6x6x6 cube (16..=231) and the grey ramp (232..=255)
+fn rgb_to_256(r: u8, g: u8, b: u8) -> u8 {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let nearest = |v: u8| (0..6).min_by_key(|&i| (LEVELS[i] as i32 - v as i32).abs()).unwrap_or(0);
+    let (ri, gi, bi) = (nearest(r), nearest(g), nearest(b));
+    let cube = (LEVELS[ri], LEVELS[gi], LEVELS[bi]);
+    let grey_index = ((r as u32 + g as u32 + b as u32) / 3).saturating_sub(3).min(238) / 10;
+    let grey_level = (8 + grey_index * 10) as u8;
+    if distance((r, g, b), (grey_level, grey_level, grey_level)) < distance((r, g, b), cube) {
+        232 + grey_index as u8
+    } else {
+        16 + (36 * ri + 6 * gi + bi) as u8
+    }
+}
+
+// the 16 basic colors in SGR order, see ANSI_16
+const BASIC: [style::Color; 16] = {
+    use style::Color::*;
+    [
+        Black, DarkRed, DarkGreen, DarkYellow, DarkBlue, DarkMagenta, DarkCyan, Grey, DarkGrey, Red, Green, Yellow, Blue, Magenta,
+        Cyan, White,
+    ]
+};
+
+// the closest of the 16 basic colors
+fn rgb_to_16(r: u8, g: u8, b: u8) -> style::Color {
+    BASIC
+        .iter()
+        .zip(ANSI_16.iter())
+        .min_by_key(|(_, rgb)| distance((r, g, b), **rgb))
+        .map_or(style::Color::Reset, |(color, _)| *color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols(cells: &[Cell]) -> Vec<&str> {
+        cells.iter().map(|c| c.symbol.as_str()).collect()
+    }
+
+    #[test]
+    fn wide_graphemes_take_one_cell_per_column() {
+        assert_eq!(symbols(&Cell::from_text("a漢e\u{301}")), vec!["a", "漢", "", "e\u{301}"]);
+    }
+
+    #[test]
+    fn control_characters_cannot_reach_the_terminal() {
+        assert_eq!(Cell::from_text("a\tb").len(), 2 + TAB_WIDTH);
+        assert_eq!(symbols(&Cell::from_text("\x1b[2J\r")), vec!["\u{FFFD}", "[", "2", "J", "\u{FFFD}"]);
+    }
+
+    #[test]
+    fn repair_blanks_cut_wide_graphemes() {
+        let mut cells = Cell::from_text("漢字");
+        cells.remove(0);
+        Cell::repair_wide(&mut cells);
+        assert_eq!(symbols(&cells), vec![" ", "字", ""]);
+    }
+
+    #[test]
+    fn colors_are_downsampled() {
+        let rgb = Color::Rgb(255, 136, 0);
+        assert_eq!(term_color(&rgb, ColorSupport::Ansi256), Some(style::Color::AnsiValue(208)));
+        assert_eq!(term_color(&rgb, ColorSupport::Ansi16), Some(style::Color::DarkYellow));
+        assert_eq!(term_color(&rgb, ColorSupport::Mono), None);
+        let mut cell = Cell::new("x");
+        cell.fg = Color::BrightRed;
+        let mut out = vec![];
+        Cell::write_cells(&[cell], ColorSupport::Ansi16, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1B[0m\x1B[91mx\x1B[0m");
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{BlendMode, Cell, Color, Layer, Line, PenTuiError, Result};
+use std::thread;
+
+// Merges any number of layers into one frame, ordered by z_index and then
+// by position. Each layer's Transparency decides which of its cells let the
+// layers below show through and its BlendMode how its backgrounds mix with
+// theirs. The rows are split into bands that are merged in parallel.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Compositor {
+    pub workers: usize,
+}
+
+impl Default for Compositor {
+    fn default() -> Self {
+        Self::new(thread::available_parallelism().map_or(1, |n| n.get()).min(4))
+    }
+}
+
+impl Compositor {
+    pub fn new(workers: usize) -> Self {
+        Self {
+            workers: workers.max(1),
+        }
+    }
+    pub fn composite(&self, layers: &[&Layer], terminal_size: &(u16, u16)) -> Result<Layer> {
+        if layers.iter().any(|layer| !layer.is_filled()) {
+            return Err(PenTuiError::UnfilledMerge);
+        }
+        let mut layers = layers.to_vec();
+        // stable, so equal z_index keep their order
+        layers.sort_by_key(|layer| layer.z_index);
+        let layers = layers.as_slice();
+        let (width, height) = (terminal_size.0 as usize, terminal_size.1 as usize);
+        let bands = self.workers.min(height).max(1);
+        let band_height = height.div_ceil(bands);
+        let lines = if bands == 1 {
+            (0..height).map(|y| composite_row(layers, y, width)).collect()
+        } else {
+            thread::scope(|scope| {
+                let handles: Vec<_> = (0..bands)
+                    .map(|band| {
+                        let rows = band * band_height..((band + 1) * band_height).min(height);
+                        scope.spawn(move || rows.map(|y| composite_row(layers, y, width)).collect::<Vec<Line>>())
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().unwrap())
+                    .collect()
+            })
+        };
+        let mut frame = Layer::blank(&(0, 0));
+        frame.set(lines);
+        Ok(frame)
+    }
+}
+
+fn composite_row(layers: &[&Layer], y: usize, width: usize) -> Line {
+    let mut line = Line::blank(width);
+    let cells = line.line_content.get_mut_value();
+    for (x, cell) in cells.iter_mut().enumerate() {
+        for (z, layer) in layers.iter().enumerate() {
+            let top = match layer.screen_cell(x, y) {
+                Some(top) => top,
+                None => continue,
+            };
+            // the lowest layer is the canvas and always drawn
+            if z > 0 && layer.is_see_through(top) {
+                // a see-through cell can still tint the background
+                if layer.blend_mode != BlendMode::Replace && top.bg != Color::None {
+                    cell.bg = layer.blend_mode.blend(cell.bg, top.bg);
+                }
+                continue;
+            }
+            let below = cell.bg;
+            *cell = top.clone();
+            cell.bg = layer.blend_mode.blend(below, top.bg);
+        }
+    }
+    Cell::repair_wide(cells);
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_layer_text, layer_text, LayerFillMode, Transparency};
+
+    fn layer(row: &str) -> Layer {
+        let mut layer = Layer::blank(&(row.len() as u16, 1));
+        layer.set_line(0, 0, &Line::new(row.to_string()), row.len() as u16);
+        layer
+    }
+
+    #[test]
+    fn spaces_are_transparent_by_default() {
+        let frame = Compositor::new(1).composite(&[&layer("abcd"), &layer(" x  ")], &(4, 1)).unwrap();
+        assert_layer_text(&frame, &["axcd"]);
+    }
+
+    #[test]
+    fn opaque_layers_draw_their_blanks() {
+        let mut top = layer(" x  ");
+        top.set_compositing(None, Some(Transparency::Opaque), None);
+        let frame = Compositor::new(1).composite(&[&layer("abcd"), &top], &(4, 1)).unwrap();
+        assert_layer_text(&frame, &[" x  "]);
+    }
+
+    #[test]
+    fn bands_give_the_same_frame() {
+        let base = Layer::blank(&(3, 5));
+        let mut top = Layer::new(vec![Line::new("abc".to_string()); 5]);
+        top.fill(&(3, 5), &LayerFillMode::Up(0));
+        let one = Compositor::new(1).composite(&[&base, &top], &(3, 5)).unwrap();
+        let many = Compositor::new(4).composite(&[&base, &top], &(3, 5)).unwrap();
+        assert_eq!(layer_text(&one), layer_text(&many));
+    }
+
+    #[test]
+    fn alpha_blends_rgb_backgrounds() {
+        let mut base = layer("a");
+        base.paint_layer_background(&Color::Rgb(0, 0, 0));
+        let mut top = layer("b");
+        top.paint_layer_background(&Color::Rgb(255, 255, 255));
+        top.set_compositing(None, None, Some(BlendMode::Alpha(51)));
+        let frame = Compositor::new(1).composite(&[&base, &top], &(1, 1)).unwrap();
+        assert_eq!(frame.cell(0, 0).map(|c| c.bg), Some(Color::Rgb(51, 51, 51)));
+    }
+}
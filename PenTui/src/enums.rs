@@ -1,10 +1,17 @@
+use crate::{PenTuiError, Result};
+use std::str::FromStr;
+
 pub enum ManagmentMode {
     Auto,
     //     min max
     //      v   v
     Manual(u16,u16),
 }
-#[derive(PartialEq, Eq)]
+// Where Line::fill puts the free columns. The variant names the side that
+// gets the fill, the number is the margin kept on the other side:
+// Right(p) leaves p columns on the left and pads the rest on the right,
+// so Right(0) is left aligned. Left(p) is the mirror image.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum LineFillMode {
     Center,
     //  the opposite
@@ -13,16 +20,103 @@ pub enum LineFillMode {
     Right(usize),
     Left(usize),
 }
-#[derive(PartialEq, Eq)]
+impl LineFillMode {
+    // (left, right) padding for `free` columns around the content
+    pub fn padding(&self, free: usize) -> (usize, usize) {
+        match self {
+            LineFillMode::Center => (free / 2, free - free / 2),
+            LineFillMode::Left(p) => (free.saturating_sub(*p), (*p).min(free)),
+            LineFillMode::Right(p) => ((*p).min(free), free.saturating_sub(*p)),
+        }
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum LayerFillMode {
     Up(usize),
     Down(usize),
     Center,
 }
-#[derive(Clone, Copy)]
-
+// Which cells of a layer let the layers below it show through.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Transparency {
+    // blank cells, like Line::merge
+    SpaceIsTransparent,
+    // only cells made with Cell::transparent()
+    ExplicitTransparent,
+    // every cell is drawn, blank ones too
+    Opaque,
+}
+// How the background of a layer's cell is combined with the background
+// below it. Only two Rgb colors are blended, otherwise the upper one wins.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlendMode {
+    Replace,
+    Overlay,
+    Multiply,
+    //    0 keeps the color below, 255 is the same as Replace
+    //    v
+    Alpha(u8),
+}
+impl BlendMode {
+    pub fn blend(&self, below: Color, above: Color) -> Color {
+        let (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) = (below, above) else {
+            return match (self, above) {
+                (BlendMode::Replace, _) => above,
+                (_, Color::None) => below,
+                _ => above,
+            };
+        };
+        let channel = |a: u8, b: u8| -> u8 {
+            let (a, b) = (a as u32, b as u32);
+            let value = match self {
+                BlendMode::Replace => b,
+                BlendMode::Multiply => a * b / 255,
+                BlendMode::Overlay if a < 128 => 2 * a * b / 255,
+                BlendMode::Overlay => 255 - 2 * (255 - a) * (255 - b) / 255,
+                BlendMode::Alpha(alpha) => (b * *alpha as u32 + a * (255 - *alpha as u32)) / 255,
+            };
+            value as u8
+        };
+        Color::Rgb(channel(r1, r2), channel(g1, g2), channel(b1, b2))
+    }
+}
+// How many colors the output can show. Colors beyond that are brought down
+// to the nearest one it has when a cell is written.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorSupport {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    // no colors at all, only the styles
+    Mono,
+}
+impl ColorSupport {
+    // what the environment says about the terminal this process runs in
+    pub fn detect() -> Self {
+        let var = |name: &str| std::env::var(name).unwrap_or_default().to_lowercase();
+        // https://no-color.org: set and not empty
+        if !var("NO_COLOR").is_empty() {
+            return ColorSupport::Mono;
+        }
+        let (colorterm, term) = (var("COLORTERM"), var("TERM"));
+        if colorterm == "truecolor" || colorterm == "24bit" || term.contains("truecolor") || term.contains("direct") {
+            return ColorSupport::TrueColor;
+        }
+        if term.contains("256color") {
+            return ColorSupport::Ansi256;
+        }
+        if term == "dumb" {
+            return ColorSupport::Mono;
+        }
+        ColorSupport::Ansi16
+    }
+}
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Color {
+    // nothing is written, the terminal keeps what it had
     None,
+    // back to the terminal's default color
+    Reset,
     Red,
     Green,
     Blue,
@@ -32,16 +126,265 @@ pub enum Color {
     Purple,
     Grey,
     Cyan,
+    White,
+    BrightDark,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightPurple,
+    BrightCyan,
+    BrightWhite,
+    // the 256 color palette: the 16 basic colors, a 6x6x6 cube, 24 greys
+    Indexed(u8),
     Rgb(u8,u8,u8),
 }
 
-#[derive(Clone, Copy)]
-pub enum Style {
-    Bold,
-    Italic,
-    Normal,
-    UnderLined,
-    Strike,
+// xterm's default values for the 16 basic colors, in SGR order
+pub(crate) const ANSI_16: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+pub(crate) fn indexed_to_rgb(index: u8) -> (u8, u8, u8) {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match index {
+        0..=15 => ANSI_16[index as usize],
+        16..=231 => {
+            let i = (index - 16) as usize;
+            (LEVELS[i / 36], LEVELS[i / 6 % 6], LEVELS[i % 6])
+        }
+        _ => {
+            let grey = 8 + (index - 232) * 10;
+            (grey, grey, grey)
+        }
+    }
+}
+
+impl Color {
+    // None and Reset depend on the terminal and have no value
+    pub fn to_rgb(&self) -> Option<(u8, u8, u8)> {
+        let basic = |index: usize| Some(ANSI_16[index]);
+        match self {
+            Color::None | Color::Reset => None,
+            Color::Dark => basic(0),
+            Color::Red => basic(1),
+            Color::Green => basic(2),
+            Color::Yellow => basic(3),
+            Color::Blue => basic(4),
+            Color::Purple => basic(5),
+            Color::Cyan => basic(6),
+            Color::White => basic(7),
+            Color::BrightDark => basic(8),
+            Color::BrightRed => basic(9),
+            Color::BrightGreen => basic(10),
+            Color::BrightYellow => basic(11),
+            Color::BrightBlue => basic(12),
+            Color::BrightPurple => basic(13),
+            Color::BrightCyan => basic(14),
+            Color::BrightWhite => basic(15),
+            Color::Orange => Some((255, 165, 0)),
+            Color::Grey => Some((128, 128, 128)),
+            Color::Indexed(index) => Some(indexed_to_rgb(*index)),
+            Color::Rgb(r, g, b) => Some((*r, *g, *b)),
+        }
+    }
+    //          hue in degrees
+    //          |       saturation and lightness from 0 to 1
+    //          v       v       v
+    pub fn from_hsl(h: f64, s: f64, l: f64) -> Color {
+        let (h, s, l) = (h.rem_euclid(360.0) / 60.0, s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = l - chroma / 2.0;
+        let channel = |v: f64| ((v + m) * 255.0).round() as u8;
+        Color::Rgb(channel(r), channel(g), channel(b))
+    }
+    pub fn to_hsl(&self) -> Option<(f64, f64, f64)> {
+        let (r, g, b) = self.to_rgb()?;
+        let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+        let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+        let l = (max + min) / 2.0;
+        let delta = max - min;
+        if delta == 0.0 {
+            return Some((0.0, 0.0, l));
+        }
+        let s = delta / (1.0 - (2.0 * l - 1.0).abs());
+        let h = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        Some((h, s, l))
+    }
+    // raises the lightness by `amount` (0 to 1), None and Reset stay as they are
+    pub fn lighten(&self, amount: f64) -> Color {
+        match self.to_hsl() {
+            Some((h, s, l)) => Color::from_hsl(h, s, l + amount),
+            None => *self,
+        }
+    }
+    pub fn darken(&self, amount: f64) -> Color {
+        self.lighten(-amount)
+    }
+    // `ratio` of `other` in self, 0 keeps self and 1 gives other
+    pub fn mix(&self, other: &Color, ratio: f64) -> Color {
+        let ratio = ratio.clamp(0.0, 1.0);
+        match (self.to_rgb(), other.to_rgb()) {
+            (Some(a), Some(b)) => {
+                let channel = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * ratio).round() as u8;
+                Color::Rgb(channel(a.0, b.0), channel(a.1, b.1), channel(a.2, b.2))
+            }
+            _ if ratio < 0.5 => *self,
+            _ => *other,
+        }
+    }
+}
+
+// "#ff8800", "#f80", "rgb(255, 136, 0)", a palette index like "208" or a
+// name like "bright red", ignoring case.
+impl FromStr for Color {
+    type Err = PenTuiError;
+    fn from_str(text: &str) -> Result<Self> {
+        let error = || PenTuiError::ParseColor(text.to_string());
+        let lower = text.trim().to_lowercase();
+        if let Some(hex) = lower.strip_prefix('#') {
+            let digits = hex
+                .chars()
+                .map(|c| c.to_digit(16).map(|d| d as u8))
+                .collect::<Option<Vec<u8>>>()
+                .ok_or_else(error)?;
+            return match digits[..] {
+                [r, g, b] => Ok(Color::Rgb(r * 17, g * 17, b * 17)),
+                [r1, r2, g1, g2, b1, b2] => Ok(Color::Rgb(r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2)),
+                _ => Err(error()),
+            };
+        }
+        if let Some(values) = lower.strip_prefix("rgb(").and_then(|rest| rest.strip_suffix(')')) {
+            let values = values
+                .split(',')
+                .map(|v| v.trim().parse::<u8>())
+                .collect::<std::result::Result<Vec<u8>, _>>()
+                .map_err(|_| error())?;
+            return match values[..] {
+                [r, g, b] => Ok(Color::Rgb(r, g, b)),
+                _ => Err(error()),
+            };
+        }
+        if let Ok(index) = lower.parse::<u8>() {
+            return Ok(Color::Indexed(index));
+        }
+        let name: String = lower.chars().filter(|c| !matches!(c, ' ' | '_' | '-')).collect();
+        let color = match name.as_str() {
+            "none" => Color::None,
+            "reset" | "default" => Color::Reset,
+            "black" | "dark" => Color::Dark,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "yellow" => Color::Yellow,
+            "blue" => Color::Blue,
+            "purple" | "magenta" => Color::Purple,
+            "cyan" => Color::Cyan,
+            "white" => Color::White,
+            "orange" => Color::Orange,
+            "grey" | "gray" => Color::Grey,
+            "brightblack" | "brightdark" | "darkgrey" | "darkgray" => Color::BrightDark,
+            "brightred" => Color::BrightRed,
+            "brightgreen" => Color::BrightGreen,
+            "brightyellow" => Color::BrightYellow,
+            "brightblue" => Color::BrightBlue,
+            "brightpurple" | "brightmagenta" => Color::BrightPurple,
+            "brightcyan" => Color::BrightCyan,
+            "brightwhite" => Color::BrightWhite,
+            _ => return Err(error()),
+        };
+        Ok(color)
+    }
+}
+
+bitflags::bitflags! {
+    // A set of text modifiers. A StylePatch changes it, taking modifiers
+    // away as well as adding them.
+    #[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+    pub struct Style: u16 {
+        const BOLD = 1;
+        const DIM = 1 << 1;
+        const ITALIC = 1 << 2;
+        const UNDERLINED = 1 << 3;
+        const DOUBLE_UNDERLINED = 1 << 4;
+        // wavy underline, terminals without it draw a plain one
+        const CURLY_UNDERLINED = 1 << 5;
+        const BLINK = 1 << 6;
+        // swaps the text and background colors
+        const REVERSE = 1 << 7;
+        const HIDDEN = 1 << 8;
+        const STRIKE = 1 << 9;
+        const OVERLINE = 1 << 10;
+    }
+}
+impl Style {
+    pub const NORMAL: Style = Style::empty();
+}
+
+// Takes the `remove` modifiers away, then adds the `add` ones. A plain
+// Style converts to a patch that only adds, so highlighting a row with
+// Style::REVERSE and restoring it with StylePatch::remove(Style::REVERSE)
+// leaves the rest of its modifiers alone.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct StylePatch {
+    pub add: Style,
+    pub remove: Style,
+}
+impl StylePatch {
+    pub fn new(add: Style, remove: Style) -> Self {
+        Self { add, remove }
+    }
+    pub fn add(style: Style) -> Self {
+        Self::new(style, Style::NORMAL)
+    }
+    pub fn remove(style: Style) -> Self {
+        Self::new(Style::NORMAL, style)
+    }
+    // exactly `style`, whatever was there before
+    pub fn replace(style: Style) -> Self {
+        Self::new(style, Style::all())
+    }
+    pub fn apply(&self, style: Style) -> Style {
+        style.difference(self.remove).union(self.add)
+    }
+    // one patch doing self and then `next`
+    pub fn then(&self, next: StylePatch) -> StylePatch {
+        Self::new(self.add.difference(next.remove).union(next.add), self.remove.union(next.remove))
+    }
+}
+impl From<Style> for StylePatch {
+    fn from(style: Style) -> Self {
+        Self::add(style)
+    }
 }
 
 #[derive(Clone)]
@@ -50,19 +393,53 @@ pub enum Container<T> {
 
 }
 impl<T> Container<T> {
-    pub fn get_value(&self) -> &T{
+    pub fn get_value(&self) -> &T {
         match self {
-            Container::Ref(a) => return a,
+            Container::Ref(a) => a,
         }
     }
-    pub fn get_mut_value(&mut self) -> &mut T{
+    pub fn get_mut_value(&mut self) -> &mut T {
         match self {
-            Container::Ref(a) => return a,
+            Container::Ref(a) => a,
         }
     }
-    pub fn get_ownership(self) -> T{
+    pub fn get_ownership(self) -> T {
         match self {
-            Container::Ref(a) => return a,
+            Container::Ref(a) => a,
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn style_patches_add_and_remove() {
+        let row = Style::BOLD | Style::ITALIC;
+        let highlighted = StylePatch::from(Style::REVERSE).apply(row);
+        assert_eq!(StylePatch::remove(Style::REVERSE).apply(highlighted), row);
+        assert_eq!(StylePatch::replace(Style::DIM).apply(row), Style::DIM);
+        let both = StylePatch::add(Style::BOLD).then(StylePatch::remove(Style::BOLD | Style::DIM));
+        assert_eq!(both.apply(Style::DIM | Style::ITALIC), Style::ITALIC);
+    }
+
+    #[test]
+    fn colors_parse_from_text() {
+        assert_eq!("#ff8800".parse::<Color>().unwrap(), Color::Rgb(255, 136, 0));
+        assert_eq!("#F80".parse::<Color>().unwrap(), Color::Rgb(255, 136, 0));
+        assert_eq!("rgb(1, 2,3)".parse::<Color>().unwrap(), Color::Rgb(1, 2, 3));
+        assert_eq!("208".parse::<Color>().unwrap(), Color::Indexed(208));
+        assert_eq!("Bright Red".parse::<Color>().unwrap(), Color::BrightRed);
+        assert!("#12345".parse::<Color>().is_err());
+        assert!("nope".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn hsl_round_trips() {
+        let color = Color::Rgb(255, 136, 0);
+        let (h, s, l) = color.to_hsl().unwrap();
+        assert_eq!(Color::from_hsl(h, s, l), color);
+        assert_eq!(Color::Dark.mix(&Color::BrightWhite, 1.0), Color::Rgb(255, 255, 255));
+        assert_eq!(Color::None.lighten(0.5), Color::None);
+    }
+}
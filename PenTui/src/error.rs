@@ -0,0 +1,56 @@
+use std::{fmt, io};
+
+#[derive(Debug)]
+pub enum PenTuiError {
+    OutOfBounds { index: usize, len: usize },
+    // Line::merge / Layer::merge on something that was never filled
+    UnfilledMerge,
+    //                    width height
+    //                      v    v
+    TerminalTooSmall { size: (u16, u16), min: u16 },
+    TerminalTooLarge { size: (u16, u16), max: u16 },
+    // Color::from_str, with the text that was given
+    ParseColor(String),
+    Io(io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, PenTuiError>;
+
+impl fmt::Display for PenTuiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PenTuiError::OutOfBounds { index, len } => {
+                write!(f, "PenTui: Index Out Of Bounds: index {} but the length is {}.", index, len)
+            }
+            PenTuiError::UnfilledMerge => write!(f, "PenTui: Lines and Layers must be filled before merging."),
+            PenTuiError::TerminalTooSmall { size, min } => write!(
+                f,
+                "PenTui: The terminal ({}x{}) is too small, it must be larger than {} in both directions.",
+                size.0, size.1, min
+            ),
+            PenTuiError::TerminalTooLarge { size, max } => write!(
+                f,
+                "PenTui: The terminal ({}x{}) is too large, it must be smaller than {} in both directions.",
+                size.0, size.1, max
+            ),
+            PenTuiError::ParseColor(text) => write!(f, "PenTui: \"{}\" is not a color.", text),
+            PenTuiError::Io(e) => write!(f, "PenTui: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PenTuiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PenTuiError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+// crossterm reports its errors as io::Error as well
+impl From<io::Error> for PenTuiError {
+    fn from(e: io::Error) -> Self {
+        PenTuiError::Io(e)
+    }
+}
@@ -0,0 +1,235 @@
+use crossterm::event as ct;
+use std::{io, time::Duration};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Event {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    //     width height
+    //       v    v
+    Resize(u16, u16),
+    FocusGained,
+    FocusLost,
+    Paste(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KeyEvent {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum KeyCode {
+    Char(char),
+    F(u8),
+    Enter,
+    Esc,
+    Backspace,
+    Tab,
+    BackTab,
+    Delete,
+    Insert,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Other,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    pub column: u16,
+    pub row: u16,
+    pub modifiers: KeyModifiers,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MouseEventKind {
+    Down(MouseButton),
+    Up(MouseButton),
+    Drag(MouseButton),
+    Moved,
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+impl KeyEvent {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+    pub fn is_char(&self, c: char) -> bool {
+        self.code == KeyCode::Char(c)
+    }
+    pub fn is_ctrl(&self, c: char) -> bool {
+        self.modifiers.control && self.is_char(c)
+    }
+}
+
+// Waits up to `timeout` for the next input event. Key releases are
+// dropped, so this can return None before the timeout ends. Repeats are
+// kept and look like presses, so a held key keeps scrolling.
+pub fn poll(timeout: Duration) -> io::Result<Option<Event>> {
+    if !ct::poll(timeout)? {
+        return Ok(None);
+    }
+    Ok(Event::from_crossterm(ct::read()?))
+}
+
+// Blocks until an event arrives.
+pub fn read() -> io::Result<Event> {
+    loop {
+        if let Some(event) = Event::from_crossterm(ct::read()?) {
+            return Ok(event);
+        }
+    }
+}
+
+impl Event {
+    pub fn from_crossterm(event: ct::Event) -> Option<Self> {
+        match event {
+            ct::Event::Key(key) if key.kind == ct::KeyEventKind::Release => None,
+            ct::Event::Key(key) => Some(Event::Key(KeyEvent {
+                code: key.code.into(),
+                modifiers: key.modifiers.into(),
+            })),
+            ct::Event::Mouse(mouse) => Some(Event::Mouse(MouseEvent {
+                kind: mouse.kind.into(),
+                column: mouse.column,
+                row: mouse.row,
+                modifiers: mouse.modifiers.into(),
+            })),
+            ct::Event::Resize(w, h) => Some(Event::Resize(w, h)),
+            ct::Event::FocusGained => Some(Event::FocusGained),
+            ct::Event::FocusLost => Some(Event::FocusLost),
+            ct::Event::Paste(text) => Some(Event::Paste(text)),
+        }
+    }
+}
+
+impl From<ct::KeyCode> for KeyCode {
+    fn from(code: ct::KeyCode) -> Self {
+        match code {
+            ct::KeyCode::Char(c) => KeyCode::Char(c),
+            ct::KeyCode::F(n) => KeyCode::F(n),
+            ct::KeyCode::Enter => KeyCode::Enter,
+            ct::KeyCode::Esc => KeyCode::Esc,
+            ct::KeyCode::Backspace => KeyCode::Backspace,
+            ct::KeyCode::Tab => KeyCode::Tab,
+            ct::KeyCode::BackTab => KeyCode::BackTab,
+            ct::KeyCode::Delete => KeyCode::Delete,
+            ct::KeyCode::Insert => KeyCode::Insert,
+            ct::KeyCode::Left => KeyCode::Left,
+            ct::KeyCode::Right => KeyCode::Right,
+            ct::KeyCode::Up => KeyCode::Up,
+            ct::KeyCode::Down => KeyCode::Down,
+            ct::KeyCode::Home => KeyCode::Home,
+            ct::KeyCode::End => KeyCode::End,
+            ct::KeyCode::PageUp => KeyCode::PageUp,
+            ct::KeyCode::PageDown => KeyCode::PageDown,
+            _ => KeyCode::Other,
+        }
+    }
+}
+
+impl From<ct::KeyModifiers> for KeyModifiers {
+    fn from(modifiers: ct::KeyModifiers) -> Self {
+        Self {
+            shift: modifiers.contains(ct::KeyModifiers::SHIFT),
+            control: modifiers.contains(ct::KeyModifiers::CONTROL),
+            alt: modifiers.contains(ct::KeyModifiers::ALT),
+        }
+    }
+}
+
+impl From<ct::MouseButton> for MouseButton {
+    fn from(button: ct::MouseButton) -> Self {
+        match button {
+            ct::MouseButton::Left => MouseButton::Left,
+            ct::MouseButton::Right => MouseButton::Right,
+            ct::MouseButton::Middle => MouseButton::Middle,
+        }
+    }
+}
+
+impl From<ct::MouseEventKind> for MouseEventKind {
+    fn from(kind: ct::MouseEventKind) -> Self {
+        match kind {
+            ct::MouseEventKind::Down(b) => MouseEventKind::Down(b.into()),
+            ct::MouseEventKind::Up(b) => MouseEventKind::Up(b.into()),
+            ct::MouseEventKind::Drag(b) => MouseEventKind::Drag(b.into()),
+            ct::MouseEventKind::Moved => MouseEventKind::Moved,
+            ct::MouseEventKind::ScrollUp => MouseEventKind::ScrollUp,
+            ct::MouseEventKind::ScrollDown => MouseEventKind::ScrollDown,
+            ct::MouseEventKind::ScrollLeft => MouseEventKind::ScrollLeft,
+            ct::MouseEventKind::ScrollRight => MouseEventKind::ScrollRight,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: ct::KeyCode, modifiers: ct::KeyModifiers, kind: ct::KeyEventKind) -> ct::Event {
+        ct::Event::Key(ct::KeyEvent::new_with_kind(code, modifiers, kind))
+    }
+
+    #[test]
+    fn keys_convert_and_releases_are_dropped() {
+        let press = key(ct::KeyCode::Char('c'), ct::KeyModifiers::CONTROL, ct::KeyEventKind::Press);
+        match Event::from_crossterm(press) {
+            Some(Event::Key(key)) => assert!(key.is_ctrl('c')),
+            other => panic!("expected a key, got {other:?}"),
+        }
+        let repeat = key(ct::KeyCode::Up, ct::KeyModifiers::NONE, ct::KeyEventKind::Repeat);
+        assert_eq!(
+            Event::from_crossterm(repeat),
+            Some(Event::Key(KeyEvent::new(KeyCode::Up, KeyModifiers::default())))
+        );
+        let release = key(ct::KeyCode::Up, ct::KeyModifiers::NONE, ct::KeyEventKind::Release);
+        assert_eq!(Event::from_crossterm(release), None);
+    }
+
+    #[test]
+    fn mouse_and_resize_events_convert() {
+        let mouse = ct::Event::Mouse(ct::MouseEvent {
+            kind: ct::MouseEventKind::Down(ct::MouseButton::Left),
+            column: 3,
+            row: 7,
+            modifiers: ct::KeyModifiers::SHIFT,
+        });
+        let expected = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 3,
+            row: 7,
+            modifiers: KeyModifiers {
+                shift: true,
+                ..KeyModifiers::default()
+            },
+        };
+        assert_eq!(Event::from_crossterm(mouse), Some(Event::Mouse(expected)));
+        assert_eq!(Event::from_crossterm(ct::Event::Resize(80, 24)), Some(Event::Resize(80, 24)));
+    }
+}
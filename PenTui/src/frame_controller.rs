@@ -1,139 +1,405 @@
-use crate::{Color, Container, Layer, ManagmentMode, Style};
-use crossterm::{
-    cursor,
-    terminal::{self, size},
-    ExecutableCommand,
+use crate::{
+    event, AnsiBackend, Backend, Color, ColorSupport, Compositor, Container, CrosstermBackend, Event, Layer, Line, LineFillMode, ManagmentMode, Paragraph,
+    PenTuiError, Rect, Result, StylePatch, TerminalSession, Transparency, Widget, Wrap,
 };
+use crossterm::terminal::size;
 use std::{
-    io::{Stdout, Write},
-    sync::mpsc,
-    thread,
+    io::{self, Write},
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
+// Builds a frame from the terminal size and the reason it is out of bounds.
+pub type FallbackFrame = Arc<dyn Fn(&(u16, u16), &PenTuiError) -> Layer + Send + Sync>;
+
+// What draw() shows while the terminal is outside the size bounds of the
+// ManagmentMode. Normal frames come back as soon as the size fits again.
+#[derive(Clone)]
+pub enum SizeFallback {
+    // draw() returns TerminalTooSmall / TerminalTooLarge
+    Error,
+    // a centered message asking for a larger or smaller window
+    Placeholder,
+    Custom(FallbackFrame),
+}
+
+// Timings of the last frame. dropped_frames counts every frame so far that
+// took longer than the delay.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct FrameStats {
+    pub merge_time: Duration,
+    pub draw_time: Duration,
+    pub bytes_written: usize,
+    // from the start of the frame until its draw was done
+    pub work_time: Duration,
+    pub frame_time: Duration,
+    pub dropped_frames: u64,
+}
+
+impl FrameStats {
+    pub fn fps(&self) -> f64 {
+        if self.frame_time.is_zero() {
+            return 0.0;
+        }
+        1.0 / self.frame_time.as_secs_f64()
+    }
+}
+
 pub struct FrameController {
     pub content_layers: Container<Vec<Layer>>,
-    delay: std::time::Duration,
+    // the target frame time, see set_fps
+    delay: Duration,
     //                width  height
     //                  v      v
     pub terminal_size: Container<(u16, u16)>,
     terminal_size_management: ManagmentMode,
-    stdout: Option<Stdout>,
+    // None draws to stdout through a CrosstermBackend
+    backend: Option<Box<dyn Backend + Send>>,
     pub result_frame: Layer,
+    // what is currently on screen, None forces a full redraw
+    previous_frame: Option<Layer>,
+    // counts wait() calls, drives animations
+    tick: u64,
+    size_fallback: SizeFallback,
+    compositor: Compositor,
+    frame_start: Instant,
+    // when this frame was drawn, counted from frame_start
+    drawn_after: Option<Duration>,
+    stats: FrameStats,
+    fps_overlay: bool,
+}
+
+impl Default for FrameController {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FrameController {
     pub fn new() -> Self {
+        // no terminal to ask when the output is not a tty
+        let terminal_size = size().unwrap_or((80, 24));
         Self {
             content_layers: Container::Ref(vec![]),
-            delay: std::time::Duration::from_millis(0),
-            terminal_size: Container::Ref(size().unwrap()),
+            delay: Duration::from_millis(0),
+            terminal_size: Container::Ref(terminal_size),
             terminal_size_management: ManagmentMode::Auto,
-            stdout: None,
-            result_frame: Layer::new(vec![]),
+            backend: None,
+            // filled, so draw() before the first merge_layers() works with
+            // the fps overlay as well
+            result_frame: Layer::blank(&terminal_size),
+            previous_frame: None,
+            tick: 0,
+            size_fallback: SizeFallback::Placeholder,
+            compositor: Compositor::default(),
+            frame_start: Instant::now(),
+            drawn_after: None,
+            stats: FrameStats::default(),
+            fps_overlay: false,
         }
     }
     pub fn set_values(
         &mut self,
-        delay: Option<std::time::Duration>,
+        delay: Option<Duration>,
         content_layers: Option<Vec<Layer>>,
-        stdout: Option<Stdout>,
+        output: Option<Box<dyn Write + Send>>,
     ) {
-        match content_layers {
-            Some(c) => *self.content_layers.get_mut_value() = c,
-            None => (),
-        };
-        match delay {
-            Some(d) => self.delay = d,
-            None => (),
+        if let Some(c) = content_layers {
+            *self.content_layers.get_mut_value() = c;
+        }
+        if let Some(d) = delay {
+            self.delay = d;
+        }
+        if let Some(a) = output {
+            self.set_output(a, None);
+        }
+    }
+    // Draws to `output` from now on: a file, a pipe, a pty or a socket.
+    // Without a size it is taken to be the terminal this process runs in,
+    // otherwise frames are made for `size` whatever the terminal is.
+    pub fn set_output(&mut self, output: Box<dyn Write + Send>, size: Option<(u16, u16)>) {
+        match size {
+            Some(size) => {
+                self.set_backend(Box::new(AnsiBackend::new(output, size)));
+                self.resize(size);
+            }
+            None => self.set_backend(Box::new(CrosstermBackend::new(output))),
         }
-        match stdout {
-            Some(a) => self.stdout = Some(a),
-            None => (),
+    }
+    // Overrides the detected color support of the backend, for the
+    // default one as well.
+    pub fn set_color_support(&mut self, support: ColorSupport) {
+        self.with_backend(|_, backend| backend.set_color_support(support));
+        self.force_redraw();
+    }
+    pub fn set_backend(&mut self, backend: Box<dyn Backend + Send>) {
+        self.backend = Some(backend);
+        self.force_redraw();
+    }
+    // Lends the backend to `f`. It is taken out of self for the call so
+    // that `f` can use both.
+    fn with_backend<T>(&mut self, f: impl FnOnce(&mut Self, &mut dyn Backend) -> T) -> T {
+        let mut backend = match self.backend.take() {
+            Some(backend) => backend,
+            None => Box::new(CrosstermBackend::new(io::stdout())),
+        };
+        let result = f(self, backend.as_mut());
+        if self.backend.is_none() {
+            self.backend = Some(backend);
         }
+        result
+    }
+    // Switches to the alternate screen in raw mode until the returned
+    // guard is dropped.
+    pub fn enter_session(&mut self) -> Result<TerminalSession> {
+        let session = TerminalSession::enter()?;
+        self.force_redraw();
+        Ok(session)
+    }
+    pub fn set_fps(&mut self, fps: u32) {
+        self.delay = Duration::from_secs_f64(1.0 / fps.max(1) as f64);
+    }
+    // draws the FrameStats over the top right corner of every frame
+    pub fn set_fps_overlay(&mut self, on: bool) {
+        self.fps_overlay = on;
+    }
+    pub fn stats(&self) -> FrameStats {
+        self.stats
     }
     pub fn size_managment_mode(&mut self, mode: ManagmentMode) {
         self.terminal_size_management = mode;
     }
-    pub fn set_terminal_to_current_size(&mut self) {
-        self.terminal_size = Container::Ref(size().unwrap());
+    pub fn set_size_fallback(&mut self, fallback: SizeFallback) {
+        self.size_fallback = fallback;
     }
-    pub fn draw(&mut self) {
+    pub fn set_terminal_to_current_size(&mut self) -> Result<()> {
+        let current_size = self.with_backend(|_, backend| backend.size())?;
         match self.terminal_size_management {
-            ManagmentMode::Auto => {
-                if self.terminal_size.get_value().0 <= 10 || self.terminal_size.get_value().1 <= 10 {
-                    println!("\n\n Hey User, Try to make the terminal window Larger.\n\n");
-                    std::process::exit(-2);
-                }
-                let mut i = 0 as u16;
-                for line in self.result_frame.layer_lines.get_value().iter() {
-                    if i == 0 {
-                        println!("{}", line.line_content.get_value());
-
-                        continue;
-                    }
-                    i += 1;
-
-                    print!("{}", line.line_content.get_value());
+            ManagmentMode::Auto => self.resize(current_size),
+            ManagmentMode::Manual(..) => self.set_terminal_size(current_size),
+        }
+        Ok(())
+    }
+    // Outside the size bounds of the current ManagmentMode this draws the
+    // SizeFallback instead of result_frame.
+    pub fn draw(&mut self) -> Result<()> {
+        self.with_backend(|controller, backend| controller.draw_to(backend))
+    }
+    // draw() for any Backend, a TestBackend for example
+    pub fn draw_to<B: Backend + ?Sized>(&mut self, backend: &mut B) -> Result<()> {
+        let draw_start = Instant::now();
+        let written = backend.bytes_written();
+        if let ManagmentMode::Auto = self.terminal_size_management {
+            let current_size = backend.size()?;
+            if *self.terminal_size.get_value() != current_size {
+                self.resize(current_size);
+                self.merge_layers()?;
+            }
+        }
+        let mut frame = match self.size_error() {
+            None => self.result_frame.clone(),
+            Some(error) => match &self.size_fallback {
+                SizeFallback::Error => return Err(error),
+                SizeFallback::Placeholder => Self::placeholder_frame(self.terminal_size.get_value(), &error),
+                SizeFallback::Custom(build) => build(self.terminal_size.get_value(), &error),
+            },
+        };
+        if self.fps_overlay {
+            let overlay = self.stats_layer();
+            frame = Compositor::new(1).composite(&[&frame, &overlay], self.terminal_size.get_value())?;
+        }
+        match &self.previous_frame {
+            Some(previous) if previous.layer_lines.get_value().len() == frame.layer_lines.get_value().len() => {
+                Self::write_changes(backend, previous, &frame)?;
+            }
+            _ => {
+                backend.clear()?;
+                for (y, line) in frame.layer_lines.get_value().iter().enumerate() {
+                    backend.move_cursor(0, y as u16)?;
+                    backend.write_cells(line.line_content.get_value())?;
                 }
             }
-            ManagmentMode::Manual(min, max) => {
-                if self.terminal_size.get_value().0 >= max || self.terminal_size.get_value().1 >= max {
-                    panic!("\n\n Hey User, Try to make the terminal window Smaller.\n\n");
+        }
+        backend.flush()?;
+        self.previous_frame = Some(frame);
+        self.stats.draw_time = draw_start.elapsed();
+        self.stats.bytes_written = backend.bytes_written() - written;
+        self.drawn_after = Some(self.frame_start.elapsed());
+        Ok(())
+    }
+    // The FrameStats as a one row layer in the top right corner, on top of
+    // everything else.
+    pub fn stats_layer(&self) -> Layer {
+        let stats = &self.stats;
+        let text = format!(
+            " {:.1} fps | merge {:.2}ms | draw {:.2}ms | {}B | {} dropped ",
+            stats.fps(),
+            stats.merge_time.as_secs_f64() * 1000.0,
+            stats.draw_time.as_secs_f64() * 1000.0,
+            stats.bytes_written,
+            stats.dropped_frames
+        );
+        let mut line = Line::new(text);
+        let width = self.terminal_size.get_value().0;
+        line.fit_width(width as usize);
+        line.paint_line_text(&Color::Yellow);
+        line.paint_line_background(&Color::Dark);
+        let line_width = line.width() as u16;
+        let mut layer = Layer::positioned(Rect::new(width - line_width, 0, line_width, 1));
+        layer.set_line(0, 0, &line, line_width);
+        layer.set_compositing(Some(i32::MAX), Some(Transparency::Opaque), None);
+        layer
+    }
+    fn size_error(&self) -> Option<PenTuiError> {
+        let size = *self.terminal_size.get_value();
+        let (min, max) = match self.terminal_size_management {
+            ManagmentMode::Auto => (10, u16::MAX),
+            ManagmentMode::Manual(min, max) => (min, max),
+        };
+        if size.0 >= max || size.1 >= max {
+            return Some(PenTuiError::TerminalTooLarge { size, max });
+        }
+        if size.0 <= min || size.1 <= min {
+            return Some(PenTuiError::TerminalTooSmall { size, min });
+        }
+        None
+    }
+    fn placeholder_frame(terminal_size: &(u16, u16), error: &PenTuiError) -> Layer {
+        let text = match error {
+            PenTuiError::TerminalTooLarge { .. } => "Hey User, Try to make the terminal window Smaller.",
+            _ => "Hey User, Try to make the terminal window Larger.",
+        };
+        let mut paragraph = Paragraph::from_text(text);
+        paragraph.set_values(Some(Wrap::Word), Some(LineFillMode::Center), None, None, None);
+        let rows = paragraph.rows(terminal_size.0).len() as u16;
+        let y = terminal_size.1.saturating_sub(rows) / 2;
+        let mut frame = Layer::blank(terminal_size);
+        paragraph.render(Rect::new(0, y, terminal_size.0, terminal_size.1 - y), &mut frame);
+        frame
+    }
+    // the next draw repaints every cell instead of only the changed ones
+    pub fn force_redraw(&mut self) {
+        self.previous_frame = None;
+    }
+    fn write_changes<B: Backend + ?Sized>(backend: &mut B, previous: &Layer, current: &Layer) -> io::Result<()> {
+        let rows = previous.layer_lines.get_value().iter().zip(current.layer_lines.get_value().iter());
+        for (y, (old_line, new_line)) in rows.enumerate() {
+            let old_cells = old_line.line_content.get_value();
+            let new_cells = new_line.line_content.get_value();
+            let mut x = 0;
+            while x < new_cells.len() {
+                if old_cells.get(x) == Some(&new_cells[x]) {
+                    x += 1;
+                    continue;
                 }
-                if self.terminal_size.get_value().0 <= min || self.terminal_size.get_value().1 <= min {
-                    panic!("\n\n Hey User, Try to make the terminal window Larger.\n\n");
+                let mut start = x;
+                // a wide grapheme is redrawn from its first column
+                while start > 0 && new_cells[start].is_continuation() {
+                    start -= 1;
                 }
-                let mut i = 0 as u16;
-                for line in self.result_frame.layer_lines.get_value().iter() {
-                    if i == 0 {
-                        println!("{}", line.line_content.get_value());
-
-                        continue;
-                    }
-                    i += 1;
-                    print!("{}", line.line_content.get_value());
+                while x < new_cells.len() && old_cells.get(x) != Some(&new_cells[x]) {
+                    x += 1;
                 }
+                backend.move_cursor(start as u16, y as u16)?;
+                backend.write_cells(&new_cells[start..x])?;
             }
         }
+        Ok(())
     }
-    pub fn wait(&self) {
-        std::thread::sleep(self.delay);
+    // Sleeps for what is left of the delay after this frame's work, so
+    // the frames keep their pace however long merging and drawing took.
+    pub fn wait(&mut self) {
+        std::thread::sleep(self.frame_budget());
+        self.finish_frame();
     }
-
-    pub fn clear_terminal(&mut self) {
-        self.stdout
-            .as_mut()
-            .unwrap()
-            .execute(terminal::Clear(terminal::ClearType::All))
-            .unwrap()
-            .execute(cursor::MoveTo(0, 0))
-            .unwrap();
-        self.stdout.as_mut().unwrap().write_all(b"\x1B[3J").unwrap();
-        self.stdout.as_mut().unwrap();
-        self.stdout.as_mut().unwrap().flush().unwrap();
+    // like wait(), but returns as soon as an input event arrives
+    pub fn wait_for_event(&mut self) -> Result<Option<Event>> {
+        let deadline = Instant::now() + self.frame_budget();
+        // poll_event also gives None for a dropped key release, so only the
+        // deadline ends the frame
+        let event = loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match self.poll_event(timeout)? {
+                Some(event) => break Some(event),
+                None if Instant::now() < deadline => continue,
+                None => break None,
+            }
+        };
+        self.finish_frame();
+        Ok(event)
+    }
+    // the time left in this frame
+    pub fn frame_budget(&self) -> Duration {
+        self.delay.saturating_sub(self.frame_start.elapsed())
+    }
+    // Ends the frame and starts the next one. A frame that was drawn after
+    // its delay was over counts as dropped.
+    pub fn finish_frame(&mut self) {
+        let frame_time = self.frame_start.elapsed();
+        self.stats.work_time = self.drawn_after.take().unwrap_or(frame_time);
+        if !self.delay.is_zero() && self.stats.work_time > self.delay {
+            self.stats.dropped_frames += 1;
+        }
+        self.stats.frame_time = frame_time;
+        self.frame_start = Instant::now();
+        self.advance_tick();
+    }
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+    pub fn advance_tick(&mut self) {
+        self.tick = self.tick.wrapping_add(1);
+    }
+    // the time between two frames
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+    pub fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        let event = event::poll(timeout)?;
+        if let Some(Event::Resize(width, height)) = event {
+            if let ManagmentMode::Auto = self.terminal_size_management {
+                self.resize((width, height));
+            }
+        }
+        Ok(event)
+    }
+    // Refills every content layer for the new size. Auto mode calls this
+    // by itself whenever it sees the terminal change size.
+    pub fn resize(&mut self, size: (u16, u16)) {
+        if *self.terminal_size.get_value() == size {
+            return;
+        }
+        self.set_terminal_size(size);
+        for layer in self.content_layers.get_mut_value().iter_mut() {
+            layer.refill(&size);
+        }
     }
 
-    pub fn merge_layers(&mut self) {
-        let length = self.content_layers.get_value().len();
-        let mut layers = self.content_layers.clone();
-        let terminal_size = self.terminal_size.clone();
-        let (tx, rx) = mpsc::channel();
+    pub fn clear_terminal(&mut self) -> Result<()> {
+        self.with_backend(|_, backend| {
+            backend.clear()?;
+            backend.move_cursor(0, 0)?;
+            backend.flush()
+        })?;
+        self.force_redraw();
+        Ok(())
+    }
 
-        let merging_sub_thread = thread::spawn(move || {
-            for i in 0..(length / 2) {
-                layers.get_mut_value()[i + 1] = layers.get_value()[i + 1].merge(&layers.get_value()[i], terminal_size.get_value());
-            }
-            tx.send(layers.get_value()[length / 2].clone()).unwrap();
-        });
-        let result;
-        for i in (length / 2)+1 as usize..(length - 1) as usize {
-            self.content_layers.get_mut_value()[i + 1] =
-                self.content_layers.get_value()[i + 1].merge(&self.content_layers.get_value()[i], self.terminal_size.get_value());
-        }
-        result = self.content_layers.get_value()[length - 1].clone();
-        merging_sub_thread.join().unwrap();
-        let thread_result = rx.recv().unwrap();
-        self.result_frame = result.merge(&thread_result, self.terminal_size.get_value());
+    // Composites content_layers into result_frame by z_index, see Compositor.
+    pub fn merge_layers(&mut self) -> Result<()> {
+        let merge_start = Instant::now();
+        let (size, tick) = (*self.terminal_size.get_value(), self.tick);
+        for layer in self.content_layers.get_mut_value().iter_mut() {
+            layer.set_tick(&size, tick);
+        }
+        let layers: Vec<&Layer> = self.content_layers.get_value().iter().collect();
+        self.result_frame = self.compositor.composite(&layers, self.terminal_size.get_value())?;
+        self.stats.merge_time = merge_start.elapsed();
+        Ok(())
+    }
+    // how many threads merge_layers splits the rows between
+    pub fn set_merge_workers(&mut self, workers: usize) {
+        self.compositor = Compositor::new(workers);
     }
     pub fn paint_result_layer_line(&mut self, color: &Color) {
         self.result_frame.paint_layer_text(color);
@@ -141,17 +407,192 @@ impl FrameController {
     pub fn paint_result_layer_background(&mut self, color: &Color) {
         self.result_frame.paint_layer_background(color);
     }
-    pub fn set_result_layer_style(&mut self, style: &Vec<Style>) {
+    pub fn set_result_layer_style(&mut self, style: impl Into<StylePatch>) {
         self.result_frame.set_layer_style(style);
     }
+    // the whole terminal as a Rect, the usual starting point for a Layout
+    pub fn area(&self) -> Rect {
+        Rect::from_size(self.terminal_size.get_value())
+    }
     pub fn set_terminal_size(&mut self, size: (u16, u16)) {
+        if *self.terminal_size.get_value() != size {
+            self.force_redraw();
+        }
         *self.terminal_size.get_mut_value() = size;
     }
-    pub fn manipulate_layer(&mut self,index:usize) -> &mut Layer{
-        if self.content_layers.get_value().len() < index {
-            println!("PenTui: Index Out Of Bounds.\nPenTui: Error At Function manipulate_line().");
-            std::process::exit(-3);
+    pub fn manipulate_layer(&mut self, index: usize) -> Result<&mut Layer> {
+        let len = self.content_layers.get_value().len();
+        self.content_layers
+            .get_mut_value()
+            .get_mut(index)
+            .ok_or(PenTuiError::OutOfBounds { index, len })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_layer_text, layer_text, BlendMode, Border, BorderAnim, BorderSet, Cell, LayerFillMode, TestBackend};
+
+    fn controller(size: (u16, u16)) -> FrameController {
+        let mut controller = FrameController::new();
+        controller.size_managment_mode(ManagmentMode::Manual(1, 500));
+        controller.set_terminal_size(size);
+        controller
+    }
+
+    fn text_layer(rows: &[&str]) -> Layer {
+        let mut layer = Layer::new(rows.iter().map(|row| Line::new(row.to_string())).collect());
+        layer.fill(&(rows[0].len() as u16, rows.len() as u16), &LayerFillMode::Up(0));
+        layer
+    }
+
+    #[test]
+    fn border_renders_into_its_area() {
+        let mut layer = Layer::blank(&(8, 4));
+        layer.render_widget(&Border::from_set(BorderSet::rounded()), Rect::new(0, 0, 8, 4));
+        assert_layer_text(&layer, &["╭──────╮", "│      │", "│      │", "╰──────╯"]);
+    }
+
+    #[test]
+    fn positioned_opaque_layer_covers_the_base() {
+        let mut controller = controller((8, 4));
+        let mut popup = Layer::positioned(Rect::new(2, 1, 4, 2));
+        popup.set_line(0, 0, &Line::new("xy".to_string()), 4);
+        popup.set_compositing(Some(1), Some(Transparency::Opaque), Some(BlendMode::Replace));
+        let base = text_layer(&["abcdefgh"; 4]);
+        // the popup comes first but its z_index puts it on top
+        controller.set_values(None, Some(vec![popup, base]), None);
+        controller.merge_layers().unwrap();
+        let mut backend = TestBackend::new(8, 4);
+        controller.draw_to(&mut backend).unwrap();
+        backend.assert_text(&["abcdefgh", "abxy  gh", "ab    gh", "abcdefgh"]);
+    }
+
+    #[test]
+    fn redraw_only_writes_changed_cells() {
+        let mut controller = controller((4, 2));
+        controller.set_values(None, Some(vec![text_layer(&["abcd", "efgh"])]), None);
+        controller.merge_layers().unwrap();
+        let mut backend = TestBackend::new(4, 2);
+        controller.draw_to(&mut backend).unwrap();
+        backend.assert_text(&["abcd", "efgh"]);
+        // a cell that is not rewritten keeps whatever the backend has
+        backend.buffer[0][0] = Cell::new("!");
+        controller.set_values(None, Some(vec![text_layer(&["abcd", "efgX"])]), None);
+        controller.merge_layers().unwrap();
+        controller.draw_to(&mut backend).unwrap();
+        backend.assert_text(&["!bcd", "efgX"]);
+        controller.force_redraw();
+        controller.draw_to(&mut backend).unwrap();
+        backend.assert_text(&["abcd", "efgX"]);
+    }
+
+    #[test]
+    fn auto_mode_refills_layers_for_the_new_size() {
+        let mut controller = FrameController::new();
+        controller.set_terminal_size((12, 11));
+        let mut text = Line::new("ab".to_string());
+        text.fill(&(12, 11), &LineFillMode::Center);
+        let mut text = Layer::new(vec![text]);
+        text.fill(&(12, 11), &LayerFillMode::Center);
+        let border = Layer::from_widget(Border::from_set(BorderSet::plain()), &(12, 11));
+        controller.set_values(None, Some(vec![border, text]), None);
+        controller.merge_layers().unwrap();
+        let mut backend = TestBackend::new(12, 11);
+        controller.draw_to(&mut backend).unwrap();
+        assert_eq!(backend.text()[5], "│    ab    │");
+        backend.resize(14, 12);
+        controller.draw_to(&mut backend).unwrap();
+        let rows = backend.text();
+        assert_eq!(rows[0], "┌────────────┐");
+        assert_eq!(rows[5], "│     ab     │");
+        assert_eq!(rows[11], "└────────────┘");
+    }
+
+    #[test]
+    fn animated_widget_layers_follow_the_tick() {
+        let mut border = Border::from_set(BorderSet::plain());
+        border.set_border_values(None, Some([Color::Red, Color::Green, Color::Blue, Color::Yellow, Color::Cyan]), None, None);
+        border.set_animation(Some(BorderAnim::Cycle), 0);
+        let mut controller = controller((6, 4));
+        controller.set_values(None, Some(vec![Layer::from_widget(border, &(6, 4))]), None);
+        controller.merge_layers().unwrap();
+        let first = controller.result_frame.cell(1, 0).cloned();
+        controller.advance_tick();
+        controller.merge_layers().unwrap();
+        assert_ne!(controller.result_frame.cell(1, 0).cloned(), first);
+    }
+
+    #[test]
+    fn too_small_terminals_get_the_placeholder() {
+        let mut controller = FrameController::new();
+        controller.size_managment_mode(ManagmentMode::Manual(20, 500));
+        controller.set_terminal_size((30, 5));
+        let mut backend = TestBackend::new(30, 5);
+        controller.draw_to(&mut backend).unwrap();
+        backend.assert_text(&[
+            "                              ",
+            "  Hey User, Try to make the   ",
+            "   terminal window Larger.    ",
+            "                              ",
+            "                              ",
+        ]);
+        controller.set_size_fallback(SizeFallback::Error);
+        assert!(matches!(controller.draw_to(&mut backend), Err(PenTuiError::TerminalTooSmall { .. })));
+    }
+
+    #[test]
+    fn frame_budget_does_not_count_drops() {
+        let mut controller = controller((4, 2));
+        controller.set_values(Some(Duration::from_millis(1)), None, None);
+        std::thread::sleep(Duration::from_millis(3));
+        controller.frame_budget();
+        controller.frame_budget();
+        controller.finish_frame();
+        assert_eq!(controller.stats().dropped_frames, 1);
+    }
+
+    #[test]
+    fn fps_overlay_draws_before_the_first_merge() {
+        let mut controller = controller((60, 2));
+        controller.set_fps_overlay(true);
+        let mut backend = TestBackend::new(60, 2);
+        controller.draw_to(&mut backend).unwrap();
+        assert!(backend.text()[0].trim_end().ends_with("0 dropped"));
+        controller.set_terminal_size((70, 2));
+        backend.resize(70, 2);
+        controller.draw_to(&mut backend).unwrap();
+        assert!(backend.text()[0].trim_end().ends_with("0 dropped"));
+    }
+
+    // a Write sink the test can still read after handing it over
+    #[derive(Clone, Default)]
+    struct Sink(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Write for Sink {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
         }
-        &mut self.content_layers.get_mut_value()[index]
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn set_output_draws_frames_of_the_given_size_to_the_sink() {
+        let sink = Sink::default();
+        let mut controller = FrameController::new();
+        controller.set_output(Box::new(sink.clone()), Some((12, 11)));
+        assert_eq!(*controller.terminal_size.get_value(), (12, 11));
+        let border = Layer::from_widget(Border::from_set(BorderSet::plain()), &(12, 11));
+        controller.set_values(None, Some(vec![border]), None);
+        controller.merge_layers().unwrap();
+        controller.draw().unwrap();
+        assert_eq!(layer_text(&controller.result_frame)[0], "┌──────────┐");
+        let output = String::from_utf8(sink.0.lock().unwrap().clone()).unwrap();
+        assert!(output.starts_with("\x1B[2J"));
+        assert!(output.contains("┌──────────┐"));
+        assert_eq!(controller.stats().bytes_written, output.len());
     }
 }
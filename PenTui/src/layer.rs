@@ -1,10 +1,29 @@
-use crate::{Color, Container, LayerFillMode, Line, Style};
+use crate::{BlendMode, Cell, Color, Container, LayerFillMode, Line, LineFillMode, PenTuiError, Rect, Result, StatefulWidget, StylePatch, Transparency, Widget};
+use std::sync::Arc;
+
+// Rebuilds the (already filled) lines of a widget layer for a terminal size
+// and an animation tick.
+pub type Relayout = Arc<dyn Fn(&(u16, u16), u64) -> Vec<Line> + Send + Sync>;
 
 #[derive(Clone)]
 pub struct Layer {
     pub layer_lines: Container<Vec<Line>>,
     is_filled: bool,
     is_widget: bool,
+    fill_mode: Option<LayerFillMode>,
+    padding: (usize, usize),
+    relayout: Option<Relayout>,
+    // the relayout is run again for every new tick, see set_tick
+    animated: bool,
+    tick: u64,
+    // layers with a higher z_index are composited on top, equal ones in
+    // the order of FrameController::content_layers
+    pub z_index: i32,
+    pub transparency: Transparency,
+    pub blend_mode: BlendMode,
+    // None covers the whole terminal, otherwise the lines only cover this
+    // part of it and are composited at its offset
+    pub area: Option<Rect>,
 }
 
 impl Layer {
@@ -13,75 +32,245 @@ impl Layer {
             layer_lines: Container::Ref(lines),
             is_filled: false,
             is_widget: false,
+            fill_mode: None,
+            padding: (0, 0),
+            relayout: None,
+            animated: false,
+            tick: 0,
+            z_index: 0,
+            transparency: Transparency::SpaceIsTransparent,
+            blend_mode: BlendMode::Replace,
+            area: None,
+        }
+    }
+    // A filled layer of blank cells, the canvas widgets render into. On a
+    // resize refill() only cuts or pads it with blanks, what was rendered
+    // stays as it was. Use from_widget for widgets that should be laid out
+    // again for the new size.
+    pub fn blank(terminal_size: &(u16, u16)) -> Self {
+        let mut layer = Layer::new(vec![Line::blank(terminal_size.0 as usize); terminal_size.1 as usize]);
+        layer.is_filled = true;
+        layer
+    }
+    // A filled layer of blank cells covering only `area`, for popups and
+    // sidebars. Its cells are addressed from its own top left corner.
+    pub fn positioned(area: Rect) -> Self {
+        let mut layer = Layer::blank(&area.size());
+        layer.area = Some(area);
+        layer
+    }
+    // moves a positioned layer, its cells stay as they are
+    pub fn set_position(&mut self, x: u16, y: u16) {
+        if let Some(area) = self.area.as_mut() {
+            area.x = x;
+            area.y = y;
+        }
+    }
+    // where widgets go to cover the whole layer
+    pub fn local_area(&self, terminal_size: &(u16, u16)) -> Rect {
+        Rect::from_size(&self.fill_size(terminal_size))
+    }
+    // the size fill() pads to: the terminal, or the layer's own area
+    fn fill_size(&self, terminal_size: &(u16, u16)) -> (u16, u16) {
+        match self.area {
+            Some(area) => area.size(),
+            None => *terminal_size,
         }
     }
-    pub fn new_static_widget(&mut self){
+    // A full-screen layer showing `widget`, rendered again whenever the
+    // layer is refilled for a new size, and for every new tick if the
+    // widget is animated.
+    pub fn from_widget<W>(widget: W, terminal_size: &(u16, u16)) -> Self
+    where
+        W: Widget + Send + Sync + 'static,
+    {
+        let animated = widget.is_animated();
+        let build = move |size: &(u16, u16), tick: u64| {
+            let mut layer = Layer::blank(size);
+            widget.render_tick(Rect::from_size(size), &mut layer, tick);
+            layer.layer_lines.get_ownership()
+        };
+        let mut layer = Layer::new(build(terminal_size, 0));
+        layer.new_static_widget();
+        layer.is_filled = true;
+        layer.relayout = Some(Arc::new(build));
+        layer.animated = animated;
+        layer
+    }
+    pub fn render_widget<W: Widget + ?Sized>(&mut self, widget: &W, area: Rect) {
+        widget.render(area, self);
+    }
+    pub fn render_stateful_widget<W: StatefulWidget + ?Sized>(&mut self, widget: &W, area: Rect, state: &mut W::State) {
+        widget.render(area, self, state);
+    }
+    pub fn is_filled(&self) -> bool {
+        self.is_filled
+    }
+    pub fn cell(&self, x: usize, y: usize) -> Option<&Cell> {
+        self.layer_lines.get_value().get(y)?.line_content.get_value().get(x)
+    }
+    // the cell at terminal coordinates, taking the layer's area into account
+    pub fn screen_cell(&self, x: usize, y: usize) -> Option<&Cell> {
+        match self.area {
+            None => self.cell(x, y),
+            Some(area) => {
+                let (left, top) = (area.x as usize, area.y as usize);
+                if x < left || y < top || x >= area.right() as usize || y >= area.bottom() as usize {
+                    return None;
+                }
+                self.cell(x - left, y - top)
+            }
+        }
+    }
+    pub fn cell_mut(&mut self, x: u16, y: u16) -> Option<&mut Cell> {
+        self.layer_lines
+            .get_mut_value()
+            .get_mut(y as usize)?
+            .line_content
+            .get_mut_value()
+            .get_mut(x as usize)
+    }
+    // copies the cells of `line` to (x, y), at most `max_width` of them
+    pub fn set_line(&mut self, x: u16, y: u16, line: &Line, max_width: u16) {
+        let mut cells: Vec<Cell> = line.line_content.get_value().iter().take(max_width as usize).cloned().collect();
+        Cell::repair_wide(&mut cells);
+        for (i, cell) in cells.into_iter().enumerate() {
+            if let Some(target) = self.cell_mut(x.saturating_add(i as u16), y) {
+                *target = cell;
+            }
+        }
+        if let Some(row) = self.layer_lines.get_mut_value().get_mut(y as usize) {
+            Cell::repair_wide(row.line_content.get_mut_value());
+        }
+    }
+    pub fn new_static_widget(&mut self) {
         self.is_widget = true
     }
     pub fn set(&mut self, lines: Vec<Line>) {
         self.layer_lines = Container::Ref(lines);
+        self.padding = (0, 0);
     }
-    pub fn fill(&mut self, terminal_size: &(u16, u16), fill_mode: &LayerFillMode) {
-        if self.is_widget {
-            self.is_filled = true;
+    pub fn set_compositing(&mut self, z_index: Option<i32>, transparency: Option<Transparency>, blend_mode: Option<BlendMode>) {
+        if let Some(a) = z_index {
+            self.z_index = a;
+        }
+        if let Some(a) = transparency {
+            self.transparency = a;
+        }
+        if let Some(a) = blend_mode {
+            self.blend_mode = a;
+        }
+    }
+    // whether the cell lets the layers below show through
+    pub fn is_see_through(&self, cell: &Cell) -> bool {
+        match self.transparency {
+            Transparency::SpaceIsTransparent => cell.is_blank(),
+            Transparency::ExplicitTransparent => cell.transparent,
+            Transparency::Opaque => false,
+        }
+    }
+    pub fn set_relayout<F>(&mut self, relayout: F)
+    where
+        F: Fn(&(u16, u16)) -> Vec<Line> + Send + Sync + 'static,
+    {
+        self.relayout = Some(Arc::new(move |size: &(u16, u16), _| relayout(size)));
+    }
+    // Renders an animated widget layer again for `tick`. merge_layers
+    // calls it with FrameController::tick() before every merge.
+    pub fn set_tick(&mut self, terminal_size: &(u16, u16), tick: u64) {
+        if !self.animated || self.tick == tick {
             return;
         }
-
-        let length = self.layer_lines.get_value().len() as usize;
-        let fill_size = (terminal_size.1 as i16 - length as i16) as i16;
-        self.is_filled = true;
-        match fill_mode {
-            &LayerFillMode::Center => {
-                for _ in 0..fill_size / 2 {
-                    let mut line = Line::new("".to_string());
-                    line.fill(terminal_size, &crate::LineFillMode::Center);
-                    self.layer_lines.get_mut_value().insert(0, line);
-                }
-                for _ in 0..fill_size / 2 {
-                    let mut line = Line::new("".to_string());
-                    line.fill(terminal_size, &crate::LineFillMode::Center);
-                    self.layer_lines.get_mut_value().push(line);
-                }
-            }
-            &LayerFillMode::Up(p) => {
-                let mut line = Line::new("".to_string());
-                line.fill(terminal_size, &crate::LineFillMode::Center);
-                for _ in 0..p {
-                    self.layer_lines.get_mut_value().insert(0, line.clone());
-                }
-                for _ in 0..fill_size - p as i16 {
-                    self.layer_lines.get_mut_value().push(line.clone());
-                }
-            }
-            &LayerFillMode::Down(p) => {
-                let mut line = Line::new("".to_string());
-                line.fill(terminal_size, &crate::LineFillMode::Center);
-                for _ in 0..p {
-                    self.layer_lines.get_mut_value().push(line.clone());
-                }
-                for _ in 0..fill_size - 1 - p as i16{
-                    self.layer_lines.get_mut_value().insert(0, line.clone());
+        self.tick = tick;
+        if let Some(relayout) = &self.relayout {
+            self.layer_lines = Container::Ref(relayout(&self.fill_size(terminal_size), tick));
+        }
+    }
+    // Brings a filled layer to a new terminal size, either through its
+    // relayout function or by refilling every line with its old fill mode.
+    // Positioned layers keep the size of their area.
+    pub fn refill(&mut self, terminal_size: &(u16, u16)) {
+        let terminal_size = &self.fill_size(terminal_size);
+        if let Some(relayout) = &self.relayout {
+            self.layer_lines = Container::Ref(relayout(terminal_size, self.tick));
+            return;
+        }
+        let fill_mode = match self.fill_mode {
+            Some(mode) => mode,
+            None => {
+                if self.is_filled {
+                    self.resize_canvas(terminal_size);
                 }
+                return;
             }
+        };
+        let lines = self.layer_lines.get_mut_value();
+        lines.truncate(lines.len().saturating_sub(self.padding.1));
+        lines.drain(0..self.padding.0.min(lines.len()));
+        for line in lines.iter_mut() {
+            line.refill(terminal_size);
+        }
+        self.is_filled = false;
+        self.fill(terminal_size, &fill_mode);
+    }
+    // cuts or pads a filled layer that has no fill mode, a blank canvas
+    fn resize_canvas(&mut self, terminal_size: &(u16, u16)) {
+        let lines = self.layer_lines.get_mut_value();
+        lines.resize(terminal_size.1 as usize, Line::blank(terminal_size.0 as usize));
+        for line in lines.iter_mut() {
+            line.fit_width(terminal_size.0 as usize);
+        }
+    }
+    pub fn fill(&mut self, terminal_size: &(u16, u16), fill_mode: &LayerFillMode) {
+        let terminal_size = &self.fill_size(terminal_size);
+        self.is_filled = true;
+        if self.is_widget {
+            return;
+        }
+        let fill_size = (terminal_size.1 as usize).saturating_sub(self.layer_lines.get_value().len());
+        let (up, down) = match fill_mode {
+            LayerFillMode::Center => (fill_size / 2, fill_size - fill_size / 2),
+            LayerFillMode::Up(p) => ((*p).min(fill_size), fill_size.saturating_sub(*p)),
+            LayerFillMode::Down(p) => (fill_size.saturating_sub(*p), (*p).min(fill_size)),
+        };
+        let line = Line::blank(terminal_size.0 as usize);
+        let lines = self.layer_lines.get_mut_value();
+        lines.splice(0..0, vec![line.clone(); up]);
+        lines.extend(vec![line; down]);
+        self.fill_mode = Some(*fill_mode);
+        self.padding = (up, down);
+    }
+    // Places the (unfilled) lines inside `area`: they are cut or padded to
+    // the area's size and everything around it is left blank.
+    pub fn fill_area(&mut self, terminal_size: &(u16, u16), area: &Rect) {
+        let lines = self.layer_lines.get_mut_value();
+        lines.resize(area.height as usize, Line::new(String::new()));
+        for line in lines.iter_mut() {
+            line.fit_width(area.width as usize);
+            line.fill(terminal_size, &LineFillMode::Right(area.x as usize));
         }
+        self.fill(terminal_size, &LayerFillMode::Up(area.y as usize));
     }
     pub fn debug_view(&self) {
         for line in self.layer_lines.get_value().iter() {
-            println!("{}", line.line_content.get_value());
+            println!("{}", line.to_ansi_string());
         }
         panic!("\n   ^\nYour Layer.\n");
     }
-    pub fn merge(&self, other_layer: &Layer, terminal_size: &(u16, u16)) -> Layer {
+    pub fn merge(&self, other_layer: &Layer) -> Result<Layer> {
         if !self.is_filled || !other_layer.is_filled {
-            panic!("\n\nTUI LIB : Layers must be filled before merging.\n\n");
+            return Err(PenTuiError::UnfilledMerge);
         }
-        let mut result_layer = Layer::new(vec![]);
-        result_layer.fill(&terminal_size, &LayerFillMode::Center);
-        for i in 1..terminal_size.1 as usize {
-            result_layer.layer_lines.get_mut_value()[i - 1] =
-                self.layer_lines.get_value()[i - 1].merge(&other_layer.layer_lines.get_value()[i - 1]);
-        }
-        result_layer
+        let lines = self
+            .layer_lines
+            .get_value()
+            .iter()
+            .zip(other_layer.layer_lines.get_value().iter())
+            .map(|(line, other_line)| line.merge(other_line))
+            .collect::<Result<Vec<Line>>>()?;
+        let mut result_layer = Layer::new(lines);
+        result_layer.is_filled = true;
+        Ok(result_layer)
     }
     pub fn paint_layer_text(&mut self, color: &Color) {
         for line in self.layer_lines.get_mut_value().iter_mut() {
@@ -93,19 +282,20 @@ impl Layer {
             line.paint_line_background(color);
         }
     }
-    pub fn set_layer_style(&mut self, style: &Vec<Style>) {
-        if self.is_widget == true {
+    pub fn set_layer_style(&mut self, style: impl Into<StylePatch>) {
+        let patch = style.into();
+        if self.is_widget {
             return;
         }
         for line in self.layer_lines.get_mut_value().iter_mut() {
-            line.set_line_style(style);
+            line.set_line_style(patch);
         }
     }
-    pub fn manipulate_line(&mut self,index:usize) -> &mut Line{
-        if self.layer_lines.get_value().len() < index {
-            println!("PenTui: Index Out Of Bounds.\nPenTui: Error At Function manipulate_line().");
-            std::process::exit(-3);
-        }
-        &mut self.layer_lines.get_mut_value()[index]
+    pub fn manipulate_line(&mut self, index: usize) -> Result<&mut Line> {
+        let len = self.layer_lines.get_value().len();
+        self.layer_lines
+            .get_mut_value()
+            .get_mut(index)
+            .ok_or(PenTuiError::OutOfBounds { index, len })
     }
 }
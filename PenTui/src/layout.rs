@@ -0,0 +1,230 @@
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Rect {
+    pub fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Self { x, y, width, height }
+    }
+    // the whole terminal
+    pub fn from_size(terminal_size: &(u16, u16)) -> Self {
+        Self::new(0, 0, terminal_size.0, terminal_size.1)
+    }
+    pub fn area(&self) -> u32 {
+        self.width as u32 * self.height as u32
+    }
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+    pub fn left(&self) -> u16 {
+        self.x
+    }
+    pub fn right(&self) -> u16 {
+        self.x.saturating_add(self.width)
+    }
+    pub fn top(&self) -> u16 {
+        self.y
+    }
+    pub fn bottom(&self) -> u16 {
+        self.y.saturating_add(self.height)
+    }
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+    pub fn inner(&self, margin: &Margin) -> Rect {
+        if self.width < margin.horizontal * 2 || self.height < margin.vertical * 2 {
+            return Rect::new(self.x, self.y, 0, 0);
+        }
+        Rect::new(
+            self.x + margin.horizontal,
+            self.y + margin.vertical,
+            self.width - margin.horizontal * 2,
+            self.height - margin.vertical * 2,
+        )
+    }
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        Rect::new(x, y, right.saturating_sub(x), bottom.saturating_sub(y))
+    }
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Margin {
+    pub horizontal: u16,
+    pub vertical: u16,
+}
+
+impl Margin {
+    pub fn new(horizontal: u16, vertical: u16) -> Self {
+        Self { horizontal, vertical }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Constraint {
+    Length(u16),
+    // 0..=100 of the space left after spacing
+    Percentage(u16),
+    Ratio(u32, u32),
+    // at least this much, grows when nothing else wants the space
+    Min(u16),
+    // grows up to this much
+    Max(u16),
+    // shares what is left with the other Fill constraints by weight
+    Fill(u16),
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Layout {
+    pub direction: Direction,
+    pub constraints: Vec<Constraint>,
+    pub margin: Margin,
+    pub spacing: u16,
+}
+
+impl Layout {
+    pub fn new(direction: Direction, constraints: Vec<Constraint>) -> Self {
+        Self {
+            direction,
+            constraints,
+            margin: Margin::default(),
+            spacing: 0,
+        }
+    }
+    pub fn set_values(&mut self, margin: Option<Margin>, spacing: Option<u16>) {
+        if let Some(m) = margin {
+            self.margin = m;
+        }
+        if let Some(s) = spacing {
+            self.spacing = s;
+        }
+    }
+    // One Rect per constraint, in order. Space that no constraint claims is
+    // left empty at the end.
+    pub fn split(&self, area: Rect) -> Vec<Rect> {
+        let area = area.inner(&self.margin);
+        let count = self.constraints.len();
+        if count == 0 {
+            return vec![];
+        }
+        let total = match self.direction {
+            Direction::Horizontal => area.width,
+            Direction::Vertical => area.height,
+        } as u32;
+        let gaps = self.spacing as u32 * (count as u32 - 1);
+        let available = total.saturating_sub(gaps);
+        let sizes = Self::solve(&self.constraints, available);
+
+        let mut position = 0u32;
+        let mut rects = vec![];
+        for size in sizes {
+            let offset = position.min(total) as u16;
+            let size = size.min(total.saturating_sub(position)) as u16;
+            rects.push(match self.direction {
+                Direction::Horizontal => Rect::new(area.x + offset, area.y, size, area.height),
+                Direction::Vertical => Rect::new(area.x, area.y + offset, area.width, size),
+            });
+            position += size as u32 + self.spacing as u32;
+        }
+        rects
+    }
+    fn solve(constraints: &[Constraint], available: u32) -> Vec<u32> {
+        // fixed sizes first, handed out in order until the space runs out
+        let mut remaining = available;
+        let mut sizes: Vec<u32> = constraints
+            .iter()
+            .map(|constraint| {
+                let wanted = match *constraint {
+                    Constraint::Length(l) => l as u32,
+                    Constraint::Percentage(p) => available * p.min(100) as u32 / 100,
+                    Constraint::Ratio(_, 0) => 0,
+                    Constraint::Ratio(a, b) => (available as u64 * a.min(b) as u64 / b as u64) as u32,
+                    Constraint::Min(m) => m as u32,
+                    Constraint::Max(_) | Constraint::Fill(_) => 0,
+                };
+                let size = wanted.min(remaining);
+                remaining -= size;
+                size
+            })
+            .collect();
+
+        // Max grows up to its cap
+        for (i, constraint) in constraints.iter().enumerate() {
+            if let Constraint::Max(m) = *constraint {
+                let grow = (m as u32).min(remaining);
+                sizes[i] += grow;
+                remaining -= grow;
+            }
+        }
+
+        // the rest goes to Fill by weight, or to Min when there is no Fill
+        let mut weights: Vec<(usize, u32)> = constraints
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| match *c {
+                Constraint::Fill(w) if w > 0 => Some((i, w as u32)),
+                _ => None,
+            })
+            .collect();
+        if weights.is_empty() {
+            weights = constraints
+                .iter()
+                .enumerate()
+                .filter_map(|(i, c)| match c {
+                    Constraint::Min(_) => Some((i, 1)),
+                    _ => None,
+                })
+                .collect();
+        }
+        let total_weight: u32 = weights.iter().map(|(_, w)| w).sum();
+        if !weights.is_empty() {
+            let share = remaining;
+            for (i, weight) in weights.iter() {
+                let grow = share * weight / total_weight;
+                sizes[*i] += grow;
+                remaining -= grow;
+            }
+            // rounding leftovers, one cell at a time from the front
+            for (i, _) in weights.iter().cycle().take(remaining as usize) {
+                sizes[*i] += 1;
+            }
+        }
+        sizes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_hands_out_fixed_sizes_first() {
+        let layout = Layout::new(Direction::Horizontal, vec![Constraint::Length(10), Constraint::Fill(1), Constraint::Percentage(50)]);
+        let widths: Vec<u16> = layout.split(Rect::new(0, 0, 100, 5)).iter().map(|r| r.width).collect();
+        assert_eq!(widths, vec![10, 40, 50]);
+    }
+
+    #[test]
+    fn split_keeps_margin_and_spacing() {
+        let mut layout = Layout::new(Direction::Vertical, vec![Constraint::Min(1), Constraint::Length(2)]);
+        layout.set_values(Some(Margin::new(1, 1)), Some(1));
+        let rects = layout.split(Rect::new(0, 0, 10, 10));
+        assert_eq!(rects, vec![Rect::new(1, 1, 8, 5), Rect::new(1, 7, 8, 2)]);
+    }
+}
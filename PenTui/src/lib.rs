@@ -1,10 +1,28 @@
+pub mod app;
+pub mod backend;
+pub mod cell;
+pub mod compositor;
 pub mod frame_controller;
 pub mod layer;
+pub mod layout;
 pub mod line;
+pub mod session;
+pub mod span;
 pub mod enums;
+pub mod error;
+pub mod event;
+pub use app::*;
+pub use backend::*;
+pub use cell::*;
+pub use compositor::*;
 pub use frame_controller::*;
 pub use layer::*;
+pub use layout::*;
 pub use line::*;
+pub use session::TerminalSession;
+pub use span::*;
 pub use enums::*;
+pub use error::{PenTuiError, Result};
+pub use event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 pub mod widgets;
 pub use widgets::*;
@@ -1,264 +1,262 @@
-use crate::{Color as color, Container, LineFillMode, Style as style};
-use colored::*;
+use crate::{Cell, Color as color, ColorSupport, Container, LineFillMode, PenTuiError, Result, Span, StylePatch};
+use std::{
+    io::{self, Write},
+    ops::Range,
+};
 
 #[derive(Clone)]
 pub struct Line {
-    pub line_content: Container<String>,
+    pub line_content: Container<Vec<Cell>>,
     is_filled: bool,
-    is_colored: bool,
+    // remembered so the line can be refilled after a resize
+    fill_mode: Option<LineFillMode>,
+    padding: (usize, usize),
+    // the cells each span covers, fill padding belongs to no span
+    spans: Vec<Range<usize>>,
 }
 
 impl Line {
     pub fn new(line_content: String) -> Self {
+        Self::from_spans(vec![Span::new(&line_content)])
+    }
+    pub fn from_spans(spans: Vec<Span>) -> Self {
+        let mut line = Self::from_cells(vec![]);
+        for span in spans.iter() {
+            line.push_span(span);
+        }
+        line
+    }
+    pub fn from_cells(cells: Vec<Cell>) -> Self {
+        let mut spans = vec![];
+        if !cells.is_empty() {
+            spans.push(0..cells.len());
+        }
         Self {
-            line_content: Container::Ref(line_content),
+            line_content: Container::Ref(cells),
             is_filled: false,
-            is_colored: false,
+            fill_mode: None,
+            padding: (0, 0),
+            spans,
         }
     }
-    pub fn from_colored_string(line_content: String) -> Self {
+    pub fn blank(width: usize) -> Self {
         Self {
-            line_content: Container::Ref(line_content),
-            is_filled: false,
-            is_colored: true,
+            line_content: Container::Ref(vec![Cell::blank(); width]),
+            is_filled: true,
+            fill_mode: None,
+            padding: (0, 0),
+            spans: vec![],
         }
     }
-    pub fn fill(&mut self, terminal_size: &(u16, u16), fill_mode: &LineFillMode) {
-        if self.is_filled == true {
-            return;
+    pub fn push_span(&mut self, span: &Span) {
+        let cells = self.line_content.get_mut_value();
+        let start = cells.len();
+        cells.extend(span.to_cells());
+        self.spans.push(start..cells.len());
+    }
+    pub fn span_count(&self) -> usize {
+        self.spans.len()
+    }
+    // The spans as they are now. A span that was painted unevenly takes its
+    // colors and style from its first cell.
+    pub fn spans(&self) -> Vec<Span> {
+        let cells = self.line_content.get_value();
+        self.spans
+            .iter()
+            .filter_map(|range| {
+                let cells = cells.get(range.clone())?;
+                let first = cells.first()?;
+                let content = cells.iter().map(|c| c.symbol.as_str()).collect::<String>();
+                Some(Span::styled(&content, first.fg, first.bg, first.modifiers))
+            })
+            .collect()
+    }
+    fn span_cells(&mut self, index: usize) -> &mut [Cell] {
+        let range = self.spans.get(index).cloned().unwrap_or(0..0);
+        let cells = self.line_content.get_mut_value();
+        let end = range.end.min(cells.len());
+        &mut cells[range.start.min(end)..end]
+    }
+    pub fn paint_span_text(&mut self, index: usize, color: &color) {
+        for cell in self.span_cells(index).iter_mut() {
+            cell.fg = *color;
         }
-        let length = self.line_content.clone().get_value().len();
-        let mut fill_size = (terminal_size.0 as i16 - length as i16) as usize;
-        if self.is_colored == true {
-            fill_size += 17;
+    }
+    pub fn paint_span_background(&mut self, index: usize, color: &color) {
+        for cell in self.span_cells(index).iter_mut() {
+            cell.bg = *color;
         }
-        match fill_mode {
-            &LineFillMode::Center => {
-                match self.line_content.get_mut_value() {
-                    a => *a = format!("{}{}", a, " ".to_string().repeat(fill_size / 2)),
-                }
-
-                match self.line_content.get_mut_value() {
-                    a => *a = format!("{}{}", " ".to_string().repeat(fill_size / 2), a),
-                }
-            }
-            &LineFillMode::Left(p) => {
-                match self.line_content.get_mut_value() {
-                    a => *a = format!("{}{}", a, " ".to_string().repeat(p)),
-                }
-                match self.line_content.get_mut_value() {
-                    a => *a = format!("{}{}", " ".to_string().repeat(fill_size - p), a),
-                }
-            }
-            &LineFillMode::Right(p) => match self.line_content.get_mut_value() {
-                a => {
-                    *a = format!("{}{}", a, " ".to_string().repeat(p));
-                    *a = format!("{}{}", " ".to_string().repeat(fill_size - p), a);
-                }
-            },
+    }
+    pub fn set_span_style(&mut self, index: usize, style: impl Into<StylePatch>) {
+        let patch = style.into();
+        for cell in self.span_cells(index).iter_mut() {
+            cell.set_style(patch);
         }
+    }
+    fn shift_spans(&mut self, offset: isize) {
+        for range in self.spans.iter_mut() {
+            *range = range.start.saturating_add_signed(offset)..range.end.saturating_add_signed(offset);
+        }
+    }
+    // visible columns, not bytes: wide graphemes own one cell per column
+    pub fn width(&self) -> usize {
+        self.line_content.get_value().len()
+    }
+    pub fn text(&self) -> String {
+        self.line_content
+            .get_value()
+            .iter()
+            .map(|c| c.symbol.as_str())
+            .collect()
+    }
+    pub fn append(&mut self, mut other: Line) {
+        other.shift_spans(self.width() as isize);
+        self.spans.extend(other.spans);
+        self.line_content
+            .get_mut_value()
+            .extend(other.line_content.get_ownership());
+    }
+    pub fn repeat(&self, times: usize) -> Line {
+        let mut line = Self::from_cells(vec![]);
+        for _ in 0..times {
+            line.append(self.clone());
+        }
+        line
+    }
+    // cuts or pads (on the right) the content to exactly `width` columns
+    pub fn fit_width(&mut self, width: usize) {
+        let cells = self.line_content.get_mut_value();
+        cells.resize(width, Cell::blank());
+        Cell::repair_wide(cells);
+        self.spans.retain(|range| range.start < width);
+        for range in self.spans.iter_mut() {
+            range.end = range.end.min(width);
+        }
+    }
+    pub fn fill(&mut self, terminal_size: &(u16, u16), fill_mode: &LineFillMode) {
+        if self.is_filled {
+            return;
+        }
+        let fill_size = (terminal_size.0 as usize).saturating_sub(self.width());
+        let (left, right) = fill_mode.padding(fill_size);
+        let cells = self.line_content.get_mut_value();
+        cells.splice(0..0, vec![Cell::blank(); left]);
+        cells.extend(vec![Cell::blank(); right]);
+        self.shift_spans(left as isize);
         self.is_filled = true;
+        self.fill_mode = Some(*fill_mode);
+        self.padding = (left, right);
+    }
+    // strips the padding added by fill() and fills again for the new size
+    pub fn refill(&mut self, terminal_size: &(u16, u16)) {
+        let fill_mode = match self.fill_mode {
+            Some(mode) => mode,
+            None => return,
+        };
+        let cells = self.line_content.get_mut_value();
+        cells.truncate(cells.len().saturating_sub(self.padding.1));
+        cells.drain(0..self.padding.0.min(cells.len()));
+        self.shift_spans(-(self.padding.0 as isize));
+        self.is_filled = false;
+        self.fill(terminal_size, &fill_mode);
     }
     pub fn debug_view(&mut self) {
-        panic!("\nYour Line:{}\n", self.line_content.get_value());
+        panic!("\nYour Line:{}\n", self.text());
     }
-    pub fn merge(&self, other_line: &Line) -> Line {
-        let line = self.line_content.clone();
-        let mut result = String::new();
+    pub fn merge(&self, other_line: &Line) -> Result<Line> {
         if !self.is_filled {
-            panic!("\n\nPENTUI LIB: Your Trying To Merge Unfilled Line.\n\n");
+            return Err(PenTuiError::UnfilledMerge);
         }
-
-        'line2_iter: for mut character in line.get_value().char_indices() {
-            for base_character in other_line.line_content.get_value().char_indices() {
-                if character.1 != ' ' {
-                    result.insert(character.0, character.1);
-                    continue 'line2_iter;
-                }
-                if base_character.0 != character.0 {
-                    continue;
-                }
-                character.1 = base_character.1;
-            }
-            result.insert(character.0, character.1);
-        }
-        Self {
+        let base = other_line.line_content.get_value();
+        let mut result: Vec<Cell> = self
+            .line_content
+            .get_value()
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| match base.get(i) {
+                Some(base_cell) if cell.is_blank() => base_cell.clone(),
+                _ => cell.clone(),
+            })
+            .collect();
+        Cell::repair_wide(&mut result);
+        Ok(Self {
             line_content: Container::Ref(result),
             is_filled: true,
-            is_colored: true,
-        }
+            fill_mode: self.fill_mode,
+            padding: self.padding,
+            spans: self.spans.clone(),
+        })
     }
 
     pub fn paint_line_text(&mut self, color: &color) {
-        match color {
-            &color::None => (),
-            &color::Blue => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().blue().to_string()
-            }
-            &color::Dark => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().black().to_string()
-            }
-            &color::Green => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().green().to_string()
-            }
-            &color::Red => {
-                *self.line_content.get_mut_value() = self.line_content.get_value().red().to_string()
-            }
-            &color::Rgb(r, g, b) => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().truecolor(r, g, b).to_string()
-            }
-            &color::Orange => {
-                *self.line_content.get_mut_value() = self
-                    .line_content
-                    .get_value()
-                    .truecolor(255, 165, 0)
-                    .to_string()
-            }
-            &color::Grey => {
-                *self.line_content.get_mut_value() = self
-                    .line_content
-                    .get_value()
-                    .truecolor(128, 128, 128)
-                    .to_string()
-            }
-            &color::Purple => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().purple().to_string()
-            }
-            &color::Yellow => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().yellow().to_string()
-            }
-            &color::Cyan => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().cyan().to_string()
-            }
-        }
-        match color {
-            &color::None => self.is_colored = false,
-            _ => self.is_colored = true,
+        for cell in self.line_content.get_mut_value().iter_mut() {
+            cell.fg = *color;
         }
     }
     pub fn paint_line_background(&mut self, color: &color) {
-        match color {
-            &color::None => (),
-            &color::Blue => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().on_blue().to_string()
-            }
-            &color::Dark => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().on_black().to_string()
-            }
-            &color::Green => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().on_green().to_string()
-            }
-            &color::Red => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().on_red().to_string()
-            }
-            &color::Rgb(r, g, b) => {
-                *self.line_content.get_mut_value() = self
-                    .line_content
-                    .get_value()
-                    .on_truecolor(r, g, b)
-                    .to_string();
-            }
-            &color::Orange => {
-                *self.line_content.get_mut_value() = self
-                    .line_content
-                    .get_value()
-                    .on_truecolor(255, 165, 0)
-                    .to_string()
-            }
-            &color::Grey => {
-                *self.line_content.get_mut_value() = self
-                    .line_content
-                    .get_value()
-                    .on_truecolor(128, 128, 128)
-                    .to_string()
-            }
-            &color::Purple => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().on_purple().to_string()
-            }
-            &color::Yellow => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().on_yellow().to_string()
-            }
-            &color::Cyan => {
-                *self.line_content.get_mut_value() =
-                    self.line_content.get_value().on_cyan().to_string()
-            }
-        }
-        match color {
-            &color::None => self.is_colored = false,
-            _ => self.is_colored = true,
+        for cell in self.line_content.get_mut_value().iter_mut() {
+            cell.bg = *color;
         }
     }
-    pub fn set_line_style(&mut self, style: &Vec<style>) {
-        for style in style.iter() {
-            match style {
-                &style::Normal => *self.line_content.get_mut_value() = self.line_content.get_value().normal().to_string(),
-                &style::Bold => *self.line_content.get_mut_value() = self.line_content.get_value().bold().to_string(),
-                &style::Italic => *self.line_content.get_mut_value() = self.line_content.get_value().italic().to_string(),
-                &style::UnderLined => *self.line_content.get_mut_value() = self.line_content.get_value().underline().to_string(),
-                &style::Strike => *self.line_content.get_mut_value() = self.line_content.get_value().strikethrough().to_string(),
-            }
+    pub fn set_line_style(&mut self, style: impl Into<StylePatch>) {
+        let patch = style.into();
+        for cell in self.line_content.get_mut_value().iter_mut() {
+            cell.set_style(patch);
         }
     }
-    pub fn paint_string_text(string: &String, color: &color) -> String {
-        let mut string = string.clone();
-        match color {
-            &color::None => (),
-            &color::Blue => string = string.blue().to_string(),
-            &color::Dark => string = string.black().to_string(),
-            &color::Green => string = string.green().to_string(),
-            &color::Red => string = string.red().to_string(),
-            &color::Rgb(r, g, b) => string = string.truecolor(r, g, b).to_string(),
-            &color::Orange => string = string.truecolor(255, 165, 0).to_string(),
-            &color::Grey => string = string.truecolor(128, 128, 128).to_string(),
-            &color::Purple => string = string.purple().to_string(),
-            &color::Yellow => string = string.yellow().to_string(),
-            &color::Cyan => string = string.cyan().to_string(),
+    // the color of the underline modifiers
+    pub fn paint_line_underline(&mut self, color: &color) {
+        for cell in self.line_content.get_mut_value().iter_mut() {
+            cell.underline_color = *color;
         }
-        string
     }
-    pub fn paint_string_background(string: &String, color: &color) -> String {
-        let mut string = string.clone();
-        match color {
-            &color::None => (),
-            &color::Blue => string = string.on_blue().to_string(),
-            &color::Dark => string = string.on_black().to_string(),
-            &color::Green => string = string.on_green().to_string(),
-            &color::Red => string = string.on_red().to_string(),
-            &color::Rgb(r, g, b) => {
-                string = string.on_truecolor(r, g, b).to_string();
-            }
-            &color::Orange => string = string.on_truecolor(255, 165, 0).to_string(),
-            &color::Grey => string = string.on_truecolor(128, 128, 128).to_string(),
-            &color::Purple => string = string.on_purple().to_string(),
-            &color::Yellow => string = string.on_yellow().to_string(),
-            &color::Cyan => string = string.on_cyan().to_string(),
-        }
-        string
+    pub fn write_ansi<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        Cell::write_cells(self.line_content.get_value(), ColorSupport::detect(), out)
     }
-    pub fn set_string_style(string: &String, style: &Vec<style>) -> String {
-        let mut string = string.clone();
-        for style in style.iter() {
-            match style {
-                &style::Normal => string = string.normal().to_string(),
-                &style::Bold => string = string.bold().to_string(),
-                &style::Italic => string = string.italic().to_string(),
-                &style::UnderLined => string = string.underline().to_string(),
-                &style::Strike => string = string.strikethrough().to_string(),
-            }
-        }
-        string
+    pub fn to_ansi_string(&self) -> String {
+        let mut out = vec![];
+        self.write_ansi(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_layer_text, Layer, Style};
+
+    fn filled(text: &str, mode: LineFillMode) -> String {
+        let mut line = Line::new(text.to_string());
+        line.fill(&(8, 1), &mode);
+        line.text()
+    }
+
+    #[test]
+    fn fill_modes_pad_the_named_side() {
+        assert_eq!(filled("ab", LineFillMode::Right(1)), " ab     ");
+        assert_eq!(filled("ab", LineFillMode::Left(1)), "     ab ");
+        assert_eq!(filled("ab", LineFillMode::Center), "   ab   ");
+    }
+
+    #[test]
+    fn spans_follow_their_cells_through_fill_and_refill() {
+        let mut line = Line::from_spans(vec![Span::new("ab"), Span::new("cd")]);
+        line.fill(&(8, 1), &LineFillMode::Center);
+        line.paint_span_text(1, &color::Red);
+        line.refill(&(10, 1));
+        line.paint_span_background(0, &color::Blue);
+        let mut layer = Layer::blank(&(10, 1));
+        layer.set_line(0, 0, &line, 10);
+        assert_layer_text(&layer, &["   abcd   "]);
+        let cells = line.line_content.get_value();
+        let fg: Vec<color> = cells[2..8].iter().map(|c| c.fg).collect();
+        let bg: Vec<color> = cells[2..8].iter().map(|c| c.bg).collect();
+        assert_eq!(fg, [color::None, color::None, color::None, color::Red, color::Red, color::None]);
+        assert_eq!(bg, [color::None, color::Blue, color::Blue, color::None, color::None, color::None]);
+        let spans = vec![
+            Span::styled("ab", color::None, color::Blue, Style::NORMAL),
+            Span::styled("cd", color::Red, color::None, Style::NORMAL),
+        ];
+        assert_eq!(line.spans(), spans);
     }
 }
@@ -0,0 +1,80 @@
+use crossterm::{
+    cursor,
+    event::{DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste, EnableFocusChange, EnableMouseCapture},
+    execute,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Once,
+    },
+};
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static PANIC_HOOK: Once = Once::new();
+
+// Holds the terminal in raw mode on the alternate screen with the cursor
+// hidden. Everything is put back when this is dropped or when the
+// program panics.
+pub struct TerminalSession {
+    _private: (),
+}
+
+impl TerminalSession {
+    pub fn enter() -> io::Result<Self> {
+        install_panic_hook();
+        terminal::enable_raw_mode()?;
+        ACTIVE.store(true, Ordering::SeqCst);
+        // from here on dropping the guard undoes whatever got through
+        let session = Self { _private: () };
+        execute!(
+            io::stdout(),
+            EnterAlternateScreen,
+            cursor::Hide,
+            EnableMouseCapture,
+            EnableBracketedPaste,
+            EnableFocusChange
+        )?;
+        Ok(session)
+    }
+    pub fn is_active() -> bool {
+        ACTIVE.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        let _ = restore();
+    }
+}
+
+// Gives the shell back its normal terminal. Does nothing when no session
+// is active, so it is safe to call from any exit path.
+pub fn restore() -> io::Result<()> {
+    if !ACTIVE.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let written = execute!(
+        io::stdout(),
+        DisableFocusChange,
+        DisableBracketedPaste,
+        DisableMouseCapture,
+        cursor::Show,
+        LeaveAlternateScreen
+    );
+    // raw mode goes even when the escape codes could not be written
+    let raw_mode = terminal::disable_raw_mode();
+    written.and(raw_mode)
+}
+
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let _ = restore();
+            previous_hook(info);
+        }));
+    });
+}
@@ -0,0 +1,47 @@
+use crate::{Cell, Color, Style};
+
+// A run of text that shares one color and style set. A Line is made of
+// any number of them.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Span {
+    pub content: String,
+    pub fg: Color,
+    pub bg: Color,
+    pub style: Style,
+}
+
+impl Span {
+    pub fn new(content: &str) -> Self {
+        Self::styled(content, Color::None, Color::None, Style::NORMAL)
+    }
+    pub fn styled(content: &str, fg: Color, bg: Color, style: Style) -> Self {
+        Self {
+            content: content.to_string(),
+            fg,
+            bg,
+            style,
+        }
+    }
+    pub fn set_values(&mut self, fg: Option<Color>, bg: Option<Color>, style: Option<Style>) {
+        if let Some(a) = fg {
+            self.fg = a;
+        }
+        if let Some(a) = bg {
+            self.bg = a;
+        }
+        if let Some(a) = style {
+            self.style = a;
+        }
+    }
+    pub fn to_cells(&self) -> Vec<Cell> {
+        Cell::from_text(&self.content)
+            .into_iter()
+            .map(|mut cell| {
+                cell.fg = self.fg;
+                cell.bg = self.bg;
+                cell.modifiers = self.style;
+                cell
+            })
+            .collect()
+    }
+}
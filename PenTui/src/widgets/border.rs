@@ -0,0 +1,362 @@
+use crate::{Cell, Color, Layer, Line, LineFillMode, Rect, Style, Widget};
+
+// How the border colors and styles travel over the border characters each
+// tick. The characters themselves stay where they are.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BorderAnim {
+    // clockwise around the whole frame
+    Cycle,
+    // back and forth along the top and bottom edges
+    LeftAndRight,
+    // up and down along the side edges
+    UpDown,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BorderSet {
+    pub top_left: String,
+    pub top_right: String,
+    pub bottom_left: String,
+    pub bottom_right: String,
+    pub top: String,
+    pub bottom: String,
+    pub left: String,
+    pub right: String,
+}
+
+impl BorderSet {
+    pub fn new(corners: [&str; 4], top: &str, bottom: &str, left: &str, right: &str) -> Self {
+        Self {
+            top_left: corners[0].to_string(),
+            top_right: corners[1].to_string(),
+            bottom_left: corners[2].to_string(),
+            bottom_right: corners[3].to_string(),
+            top: top.to_string(),
+            bottom: bottom.to_string(),
+            left: left.to_string(),
+            right: right.to_string(),
+        }
+    }
+    //          top-corners top  middle down-corner down
+    //               1       2     3        4        5
+    pub fn from_five(chars: [&str; 5]) -> Self {
+        Self::new([chars[0], chars[0], chars[3], chars[3]], chars[1], chars[4], chars[2], chars[2])
+    }
+    pub fn plain() -> Self {
+        Self::new(["┌", "┐", "└", "┘"], "─", "─", "│", "│")
+    }
+    pub fn rounded() -> Self {
+        Self::new(["╭", "╮", "╰", "╯"], "─", "─", "│", "│")
+    }
+    pub fn double() -> Self {
+        Self::new(["╔", "╗", "╚", "╝"], "═", "═", "║", "║")
+    }
+    pub fn thick() -> Self {
+        Self::new(["┏", "┓", "┗", "┛"], "━", "━", "┃", "┃")
+    }
+    pub fn ascii() -> Self {
+        Self::new(["+", "+", "+", "+"], "-", "-", "|", "|")
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BorderSides {
+    pub top: bool,
+    pub bottom: bool,
+    pub left: bool,
+    pub right: bool,
+}
+
+impl BorderSides {
+    pub fn all() -> Self {
+        Self {
+            top: true,
+            bottom: true,
+            left: true,
+            right: true,
+        }
+    }
+}
+
+// Text drawn over the top (title) or bottom (subtitle) edge, between the
+// corners. Its styling comes from the spans of `content`.
+#[derive(Clone)]
+pub struct BorderTitle {
+    pub content: Line,
+    // same meaning as for Line::fill, within the edge
+    pub alignment: LineFillMode,
+}
+
+impl BorderTitle {
+    pub fn new(content: Line, alignment: LineFillMode) -> Self {
+        Self { content, alignment }
+    }
+    pub fn from_text(text: &str) -> Self {
+        Self::new(Line::new(text.to_string()), LineFillMode::Right(1))
+    }
+}
+
+#[derive(Clone)]
+pub struct Border {
+    pub border_set: BorderSet,
+    // colors and styles keep the five slots:
+    //          top-corners top  sides down-corner down
+    //               1       2     3        4        5
+    pub border_chars_color: [Color; 5],
+    pub border_background_color: [Color; 5],
+    pub border_style: [Style; 5],
+    pub sides: BorderSides,
+    pub title: Option<BorderTitle>,
+    pub subtitle: Option<BorderTitle>,
+    pub anim: Option<BorderAnim>,
+    // used by render(), Layer::from_widget passes FrameController::tick()
+    // instead on its own
+    pub tick: u64,
+}
+
+impl Default for Border {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Border {
+    pub fn new() -> Self {
+        Self::from_set(BorderSet::from_five([".", "-", "|", "'", "-"]))
+    }
+    pub fn from_set(border_set: BorderSet) -> Self {
+        Self {
+            border_set,
+            border_chars_color: [Color::None; 5],
+            border_background_color: [Color::None; 5],
+            border_style: Default::default(),
+            sides: BorderSides::all(),
+            title: None,
+            subtitle: None,
+            anim: None,
+            tick: 0,
+        }
+    }
+    pub fn set_border_values(
+        &mut self,
+        border_set: Option<BorderSet>,
+        border_chars_color: Option<[Color; 5]>,
+        border_background_color: Option<[Color; 5]>,
+        border_style: Option<[Style; 5]>,
+    ) {
+        if let Some(a) = border_set {
+            self.border_set = a;
+        }
+        if let Some(a) = border_chars_color {
+            self.border_chars_color = a;
+        }
+        if let Some(a) = border_background_color {
+            self.border_background_color = a;
+        }
+        if let Some(a) = border_style {
+            self.border_style = a;
+        }
+    }
+    pub fn set_titles(&mut self, title: Option<Option<BorderTitle>>, subtitle: Option<Option<BorderTitle>>) {
+        if let Some(a) = title {
+            self.title = a;
+        }
+        if let Some(a) = subtitle {
+            self.subtitle = a;
+        }
+    }
+    pub fn set_sides(&mut self, sides: BorderSides) {
+        self.sides = sides;
+    }
+    pub fn set_animation(&mut self, anim: Option<BorderAnim>, tick: u64) {
+        self.anim = anim;
+        self.tick = tick;
+    }
+    // the part of `area` left for content once the border is drawn
+    pub fn inner(&self, area: Rect) -> Rect {
+        let set = &self.border_set;
+        let left = if self.sides.left { Line::new(set.left.clone()).width() as u16 } else { 0 };
+        let right = if self.sides.right { Line::new(set.right.clone()).width() as u16 } else { 0 };
+        let top = self.sides.top as u16;
+        let bottom = self.sides.bottom as u16;
+        if area.width < left + right || area.height < top + bottom {
+            return Rect::new(area.x, area.y, 0, 0);
+        }
+        Rect::new(area.x + left, area.y + top, area.width - left - right, area.height - top - bottom)
+    }
+    // the styled cells of `symbol` in a color slot, more than one if wide
+    fn cells(&self, symbol: &str, slot: usize) -> Line {
+        Line::from_cells(
+            Cell::from_text(symbol)
+                .into_iter()
+                .map(|mut cell| {
+                    cell.fg = self.border_chars_color[slot];
+                    cell.bg = self.border_background_color[slot];
+                    cell.modifiers = self.border_style[slot];
+                    cell
+                })
+                .collect(),
+        )
+    }
+    // the border cells whose styles move together, in the order they move
+    fn anim_paths(&self, anim: BorderAnim, area: Rect) -> Vec<Vec<(u16, u16)>> {
+        let (left, top) = (area.left(), area.top());
+        let (right, bottom) = (area.right() - 1, area.bottom() - 1);
+        let top_row: Vec<(u16, u16)> = (left..=right).map(|x| (x, top)).collect();
+        let bottom_row: Vec<(u16, u16)> = (left..=right).map(|x| (x, bottom)).collect();
+        let left_side: Vec<(u16, u16)> = (top..=bottom).map(|y| (left, y)).collect();
+        let right_side: Vec<(u16, u16)> = (top..=bottom).map(|y| (right, y)).collect();
+        match anim {
+            BorderAnim::LeftAndRight => vec![top_row, bottom_row],
+            BorderAnim::UpDown => vec![left_side, right_side],
+            BorderAnim::Cycle => {
+                let mut path = top_row;
+                path.extend(right_side.into_iter().skip(1));
+                path.extend(bottom_row.into_iter().rev().skip(1));
+                path.extend(left_side.into_iter().rev().skip(1).take((area.height as usize).saturating_sub(2)));
+                vec![path]
+            }
+        }
+    }
+    fn animate(&self, anim: BorderAnim, area: Rect, buf: &mut Layer, tick: u64) {
+        let sides = self.sides;
+        let on_border = |&(x, y): &(u16, u16)| {
+            (sides.top && y == area.top())
+                || (sides.bottom && y == area.bottom() - 1)
+                || (sides.left && x == area.left())
+                || (sides.right && x == area.right() - 1)
+        };
+        for mut path in self.anim_paths(anim, area) {
+            path.retain(on_border);
+            if path.len() < 2 {
+                continue;
+            }
+            let len = path.len() as u64;
+            let offset = match anim {
+                BorderAnim::Cycle => tick % len,
+                // bounce between both ends instead of wrapping
+                _ => {
+                    let t = tick % (2 * (len - 1));
+                    t.min(2 * (len - 1) - t)
+                }
+            } as usize;
+            let styles: Vec<Option<Cell>> = path.iter().map(|&(x, y)| buf.cell_mut(x, y).map(|c| c.clone())).collect();
+            for (i, &(x, y)) in path.iter().enumerate() {
+                let from = (i + path.len() - offset) % path.len();
+                if let (Some(target), Some(Some(style))) = (buf.cell_mut(x, y), styles.get(from)) {
+                    target.fg = style.fg;
+                    target.bg = style.bg;
+                    target.modifiers = style.modifiers;
+                    target.underline_color = style.underline_color;
+                }
+            }
+        }
+    }
+    // a top or bottom row: the corners (if their side is on) around the edge
+    fn edge_row(&self, corners: (&str, &str), edge: &str, corner_slot: usize, edge_slot: usize, width: usize) -> Line {
+        let empty = Line::from_cells(vec![]);
+        let left = if self.sides.left { self.cells(corners.0, corner_slot) } else { empty.clone() };
+        let right = if self.sides.right { self.cells(corners.1, corner_slot) } else { empty };
+        let edge = self.cells(edge, edge_slot);
+        let inner_width = width.saturating_sub(left.width() + right.width());
+        let right_width = right.width();
+        let mut row = left;
+        if edge.width() > 0 {
+            row.append(edge.repeat(inner_width / edge.width()));
+        }
+        row.fit_width(width.saturating_sub(right_width));
+        row.append(right);
+        row.fit_width(width);
+        row
+    }
+    fn draw_title(&self, title: &BorderTitle, area: Rect, y: u16, buf: &mut Layer) {
+        let inner = self.inner(area);
+        let (x, width) = if inner.width > 0 { (inner.x, inner.width) } else { (area.x, area.width) };
+        let mut content = title.content.clone();
+        if content.width() > width as usize {
+            content.fit_width(width as usize);
+        }
+        let (pad, _) = title.alignment.padding(width as usize - content.width());
+        buf.set_line(x + pad as u16, y, &content, width - pad as u16);
+    }
+}
+
+impl Widget for Border {
+    fn render(&self, area: Rect, buf: &mut Layer) {
+        self.render_tick(area, buf, self.tick);
+    }
+    fn render_tick(&self, area: Rect, buf: &mut Layer, tick: u64) {
+        if area.is_empty() {
+            return;
+        }
+        let set = &self.border_set;
+        let width = area.width as usize;
+        let sides = self.sides;
+        if sides.top {
+            let row = self.edge_row((&set.top_left, &set.top_right), &set.top, 0, 1, width);
+            buf.set_line(area.x, area.top(), &row, area.width);
+        }
+        if sides.bottom && (area.height > 1 || !sides.top) {
+            let row = self.edge_row((&set.bottom_left, &set.bottom_right), &set.bottom, 3, 4, width);
+            buf.set_line(area.x, area.bottom() - 1, &row, area.width);
+        }
+        let left = self.cells(&set.left, 2);
+        let right = self.cells(&set.right, 2);
+        let right_x = area.right().saturating_sub(right.width() as u16).max(area.x);
+        let first_row = area.top() + sides.top as u16;
+        let last_row = area.bottom().saturating_sub(sides.bottom as u16);
+        for y in first_row..last_row {
+            if sides.left {
+                buf.set_line(area.x, y, &left, area.width);
+            }
+            if sides.right {
+                buf.set_line(right_x, y, &right, area.right() - right_x);
+            }
+        }
+        if let Some(anim) = self.anim {
+            self.animate(anim, area, buf, tick);
+        }
+        if let (true, Some(title)) = (sides.top, &self.title) {
+            self.draw_title(title, area, area.top(), buf);
+        }
+        if let (true, Some(subtitle)) = (sides.bottom, &self.subtitle) {
+            self.draw_title(subtitle, area, area.bottom() - 1, buf);
+        }
+    }
+    fn is_animated(&self) -> bool {
+        self.anim.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_layer_text;
+
+    #[test]
+    fn titles_sit_on_the_edges() {
+        let mut border = Border::from_set(BorderSet::plain());
+        border.set_titles(
+            Some(Some(BorderTitle::from_text("Hi"))),
+            Some(Some(BorderTitle::new(Line::new("q".to_string()), LineFillMode::Left(1)))),
+        );
+        let mut layer = Layer::blank(&(8, 3));
+        layer.render_widget(&border, Rect::new(0, 0, 8, 3));
+        assert_layer_text(&layer, &["┌─Hi───┐", "│      │", "└────q─┘"]);
+        assert_eq!(border.inner(Rect::new(0, 0, 8, 3)), Rect::new(1, 1, 6, 1));
+    }
+
+    #[test]
+    fn disabled_sides_are_left_out() {
+        let mut border = Border::from_set(BorderSet::ascii());
+        border.set_sides(BorderSides {
+            top: true,
+            bottom: false,
+            left: false,
+            right: true,
+        });
+        let mut layer = Layer::blank(&(4, 2));
+        layer.render_widget(&border, Rect::new(0, 0, 4, 2));
+        assert_layer_text(&layer, &["---+", "   |"]);
+    }
+}
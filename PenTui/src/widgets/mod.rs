@@ -0,0 +1,27 @@
+use crate::{Layer, Rect};
+
+pub mod border;
+pub mod paragraph;
+pub use border::*;
+pub use paragraph::*;
+
+// Anything that can draw itself into a region of a filled layer.
+// Cells outside `area` must be left alone.
+pub trait Widget {
+    fn render(&self, area: Rect, buf: &mut Layer);
+    // render() for animation frame `tick`. Layer::from_widget calls it
+    // with FrameController::tick() when is_animated() says so.
+    fn render_tick(&self, area: Rect, buf: &mut Layer, _tick: u64) {
+        self.render(area, buf);
+    }
+    fn is_animated(&self) -> bool {
+        false
+    }
+}
+
+// A widget whose rendering depends on state kept by the caller between
+// frames, like a scroll position or a selection.
+pub trait StatefulWidget {
+    type State;
+    fn render(&self, area: Rect, buf: &mut Layer, state: &mut Self::State);
+}
@@ -0,0 +1,266 @@
+use crate::{Cell, Layer, Line, LineFillMode, Rect, Widget};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Wrap {
+    // one row per line, cut at the right edge
+    None,
+    // break between words, splitting words longer than the area
+    Word,
+    // break at the last column that fits
+    Char,
+}
+
+#[derive(Clone)]
+pub struct Paragraph {
+    pub lines: Vec<Line>,
+    pub wrap: Wrap,
+    // same meaning as for Line::fill, with the area width as the terminal width
+    pub alignment: LineFillMode,
+    // stretch every wrapped row but the last one of a line to the full width
+    pub justify: bool,
+    //         rows  columns
+    //           v     v
+    pub scroll: (u16, u16),
+    // put at the end of any row that had to be cut
+    pub ellipsis: Option<String>,
+}
+
+impl Paragraph {
+    pub fn new(lines: Vec<Line>) -> Self {
+        Self {
+            lines,
+            wrap: Wrap::Word,
+            // left aligned
+            alignment: LineFillMode::Right(0),
+            justify: false,
+            scroll: (0, 0),
+            ellipsis: None,
+        }
+    }
+    pub fn from_text(text: &str) -> Self {
+        Self::new(text.lines().map(|l| Line::new(l.to_string())).collect())
+    }
+    pub fn set_values(
+        &mut self,
+        wrap: Option<Wrap>,
+        alignment: Option<LineFillMode>,
+        justify: Option<bool>,
+        scroll: Option<(u16, u16)>,
+        ellipsis: Option<Option<String>>,
+    ) {
+        if let Some(a) = wrap {
+            self.wrap = a;
+        }
+        if let Some(a) = alignment {
+            self.alignment = a;
+        }
+        if let Some(a) = justify {
+            self.justify = a;
+        }
+        if let Some(a) = scroll {
+            self.scroll = a;
+        }
+        if let Some(a) = ellipsis {
+            self.ellipsis = a;
+        }
+    }
+    // Every row the paragraph takes at `width`, before scrolling. The flag
+    // marks the last row of each source line.
+    pub fn rows(&self, width: u16) -> Vec<(Vec<Cell>, bool)> {
+        let width = width as usize;
+        let mut rows = vec![];
+        for line in self.lines.iter() {
+            let cells = line.line_content.get_value();
+            let wrapped = match self.wrap {
+                Wrap::None => {
+                    let mut row: Vec<Cell> = cells.iter().skip(self.scroll.1 as usize).cloned().collect();
+                    Cell::repair_wide(&mut row);
+                    vec![row]
+                }
+                Wrap::Char => wrap_chars(cells, width),
+                Wrap::Word => wrap_words(cells, width),
+            };
+            let count = wrapped.len();
+            rows.extend(wrapped.into_iter().enumerate().map(|(i, row)| (row, i + 1 == count)));
+        }
+        rows
+    }
+    fn ellipsis_cells(&self, like: Option<&Cell>) -> Vec<Cell> {
+        let ellipsis = match &self.ellipsis {
+            Some(e) => e,
+            None => return vec![],
+        };
+        Line::new(ellipsis.clone())
+            .line_content
+            .get_ownership()
+            .into_iter()
+            .map(|mut cell| {
+                if let Some(like) = like {
+                    cell.fg = like.fg;
+                    cell.bg = like.bg;
+                    cell.modifiers = like.modifiers;
+                    cell.underline_color = like.underline_color;
+                }
+                cell
+            })
+            .collect()
+    }
+    fn truncate(&self, row: &mut Vec<Cell>, width: usize, force: bool) {
+        if row.len() <= width && !force {
+            return;
+        }
+        let ellipsis = self.ellipsis_cells(row.last());
+        let keep = width.saturating_sub(ellipsis.len()).min(row.len());
+        row.truncate(keep);
+        row.extend(ellipsis.into_iter().take(width));
+        Cell::repair_wide(row);
+    }
+}
+
+impl Widget for Paragraph {
+    fn render(&self, area: Rect, buf: &mut Layer) {
+        if area.is_empty() {
+            return;
+        }
+        let width = area.width as usize;
+        let rows = self.rows(area.width);
+        let overflows = rows.len() > self.scroll.0 as usize + area.height as usize;
+        let visible = rows.into_iter().skip(self.scroll.0 as usize).take(area.height as usize);
+        for (y, (mut row, last_of_line)) in visible.enumerate() {
+            let last_visible = y + 1 == area.height as usize;
+            self.truncate(&mut row, width, last_visible && overflows);
+            if self.justify && !last_of_line {
+                justify(&mut row, width);
+            }
+            let mut line = Line::from_cells(row);
+            line.fill(&(area.width, 1), &self.alignment);
+            buf.set_line(area.x, area.y + y as u16, &line, area.width);
+        }
+    }
+}
+
+fn wrap_chars(cells: &[Cell], width: usize) -> Vec<Vec<Cell>> {
+    if width == 0 || cells.is_empty() {
+        return vec![vec![]];
+    }
+    let mut rows = vec![];
+    let mut rest = cells;
+    while !rest.is_empty() {
+        let cut = split_point(rest, width);
+        rows.push(rest[..cut].to_vec());
+        rest = &rest[cut..];
+    }
+    rows
+}
+
+// Where to cut `cells` so the first part fits in `width` without splitting
+// a wide grapheme. Always at least one grapheme, even if it does not fit.
+fn split_point(cells: &[Cell], width: usize) -> usize {
+    if cells.len() <= width {
+        return cells.len();
+    }
+    let mut cut = width;
+    while cut > 0 && cells[cut].is_continuation() {
+        cut -= 1;
+    }
+    if cut == 0 {
+        cut = 1 + cells[1..].iter().take_while(|c| c.is_continuation()).count();
+    }
+    cut
+}
+
+fn wrap_words(cells: &[Cell], width: usize) -> Vec<Vec<Cell>> {
+    if width == 0 {
+        return vec![vec![]];
+    }
+    let mut rows = vec![];
+    let mut row: Vec<Cell> = vec![];
+    let mut i = 0;
+    while i < cells.len() {
+        let space_start = i;
+        while i < cells.len() && cells[i].is_blank() {
+            i += 1;
+        }
+        let word_start = i;
+        while i < cells.len() && !cells[i].is_blank() {
+            i += 1;
+        }
+        let spaces = &cells[space_start..word_start];
+        let mut word = &cells[word_start..i];
+        if row.len() + spaces.len() + word.len() <= width {
+            row.extend_from_slice(spaces);
+            row.extend_from_slice(word);
+            continue;
+        }
+        // trailing spaces that do not fit are dropped without starting a row
+        if word.is_empty() {
+            break;
+        }
+        // the spaces at a break are dropped
+        if !row.is_empty() {
+            rows.push(std::mem::take(&mut row));
+        }
+        while word.len() > width {
+            let cut = split_point(word, width);
+            rows.push(word[..cut].to_vec());
+            word = &word[cut..];
+        }
+        row = word.to_vec();
+    }
+    rows.push(row);
+    rows
+}
+
+// widens the gaps between words until the row is `width` columns
+fn justify(row: &mut Vec<Cell>, width: usize) {
+    while row.last().is_some_and(|c| c.is_blank()) {
+        row.pop();
+    }
+    let gaps: Vec<usize> = (1..row.len())
+        .filter(|&i| row[i].is_blank() && !row[i - 1].is_blank())
+        .collect();
+    if gaps.is_empty() || row.len() >= width {
+        return;
+    }
+    let extra = width - row.len();
+    // insert from the back so the earlier positions stay valid
+    for (n, &gap) in gaps.iter().enumerate().rev() {
+        let count = extra / gaps.len() + usize::from(n < extra % gaps.len());
+        let blank = row[gap].clone();
+        row.splice(gap..gap, vec![blank; count]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_layer_text;
+
+    fn render(paragraph: &Paragraph, size: (u16, u16)) -> Layer {
+        let mut layer = Layer::blank(&size);
+        layer.render_widget(paragraph, Rect::from_size(&size));
+        layer
+    }
+
+    #[test]
+    fn words_wrap_at_the_area_width() {
+        let paragraph = Paragraph::from_text("the quick brown fox");
+        assert_layer_text(&render(&paragraph, (10, 3)), &["the quick ", "brown fox ", "          "]);
+    }
+
+    #[test]
+    fn cut_rows_get_the_ellipsis() {
+        let mut paragraph = Paragraph::from_text("the quick brown fox");
+        paragraph.set_values(Some(Wrap::None), Some(LineFillMode::Center), None, None, Some(Some("…".to_string())));
+        assert_layer_text(&render(&paragraph, (8, 1)), &["the qui…"]);
+    }
+
+    #[test]
+    fn trailing_spaces_at_a_break_add_no_row() {
+        let mut paragraph = Paragraph::from_text("done ");
+        paragraph.set_values(None, None, None, None, Some(Some("…".to_string())));
+        assert_layer_text(&render(&paragraph, (4, 1)), &["done"]);
+        let paragraph = Paragraph::from_text("done  \nnext");
+        assert_layer_text(&render(&paragraph, (4, 2)), &["done", "next"]);
+    }
+}